@@ -1,11 +1,8 @@
 //! Log into a 1.8.*/1.16.[4/5] server.
 
 use std::env;
-use std::thread;
-use std::time;
 
 use tetsu::client;
-use tetsu::errors;
 
 fn main() {
     env_logger::builder()
@@ -15,7 +12,8 @@ fn main() {
     let user = client::mojang::User::authenticate(
         env::var("MOJANG_USER").unwrap(),
         env::var("MOJANG_USER_PWD").unwrap(),
-    );
+    )
+    .unwrap();
 
     let mut client = client::Client::new("127.0.0.1", None, None).unwrap();
 
@@ -24,13 +22,7 @@ fn main() {
     loop {
         match client.read_event() {
             Ok(e) => println!("{:?}", e),
-            Err(e) => match e {
-                errors::ConnectionError::LockError(_) => {
-                    thread::sleep(time::Duration::from_millis(50));
-                    continue;
-                }
-                errors::ConnectionError::Error(e) => panic!("Error while reading event: {:?}", e),
-            },
+            Err(e) => panic!("Error while reading event: {}", e),
         }
     }
 }
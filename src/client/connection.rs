@@ -1,12 +1,23 @@
 //! Basic server connection utilities.
 
+use super::mojang;
+use super::proxy::{self, ProxyConfig};
 use crate::crypto::*;
 use crate::errors::*;
 use crate::event::*;
+use crate::serialization::Readable;
+use crate::versions::common::{checked_len, VarInt, MAX_PACKET_LEN};
 
+use std::io::{self, Read};
+
+use flate2::read::ZlibDecoder;
 pub use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use log::{debug, info, warn};
 
-use log::{debug, info};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 /// Encrypted connection to a Minecraft server.
 pub struct EncryptedConnection {
@@ -18,21 +29,49 @@ pub struct EncryptedConnection {
     pub protocol_version: ProtocolVersion,
     /// Compression threshold.
     compression_threshold: i32,
+    /// Packet framing of received bytes.
+    frames: FrameReader,
     /// Internal event dispatcher.
-    dispatcher: dispatcher::EventDispatcher<EncryptedTcpStream, EncryptedTcpStream>,
+    dispatcher: dispatcher::EventDispatcher<io::Cursor<Vec<u8>>, EncryptedTcpStream>,
 }
 
 impl EncryptedConnection {
     /// Construct a new Encrypted Connection to a server.
     #[inline]
     pub fn new(address: &str, port: u16, protocol_version: ProtocolVersion) -> TetsuResult<Self> {
-        Ok(Self {
-            stream: EncryptedTcpStream::connect(&format!("{}:{}", address, port), None)?,
+        Ok(Self::from_stream(
+            EncryptedTcpStream::connect(&format!("{}:{}", address, port), None)?,
+            protocol_version,
+        ))
+    }
+
+    /// Construct a new Encrypted Connection to a server, tunneled through a SOCKS5 proxy.
+    #[inline]
+    pub fn new_via_proxy(
+        address: &str,
+        port: u16,
+        protocol_version: ProtocolVersion,
+        proxy: ProxyConfig,
+    ) -> TetsuResult<Self> {
+        Ok(Self::from_stream(
+            EncryptedTcpStream::from_stream(
+                proxy::connect(&format!("{}:{}", address, port), &proxy)?,
+                None,
+            )?,
+            protocol_version,
+        ))
+    }
+
+    #[inline]
+    fn from_stream(stream: EncryptedTcpStream, protocol_version: ProtocolVersion) -> Self {
+        Self {
+            stream,
             state: EventState::Status,
             protocol_version,
             compression_threshold: 0,
+            frames: FrameReader::default(),
             dispatcher: dispatcher::EventDispatcher::new(&protocol_version),
-        })
+        }
     }
 
     /// Set the current state of the the connection.
@@ -44,25 +83,66 @@ impl EncryptedConnection {
         );
         debug!(
             "TCP_NODELAY {}",
-            match self.stream.get_nodelay() {
-                Ok(b) => b,
-                _ => false,
-            }
+            self.stream.get_nodelay().unwrap_or_default()
         );
         self.state = *state;
     }
 
+    /// Set the read timeout of the internal `TcpStream`.
+    /// A timed out read returns an [`Error::Io`] of kind `WouldBlock` or `TimedOut`.
+    #[inline]
+    pub fn set_read_timeout(&mut self, dur: Option<Duration>) -> TetsuResult<()> {
+        self.stream.set_read_timeout(dur)
+    }
+
     /// Set the packet compression threshold.
     #[inline]
     pub fn set_compression_threshold(&mut self, compression_threshold: i32) {
         self.compression_threshold = compression_threshold;
     }
 
+    /// Get the packet compression threshold. Compression is disabled if this is not positive.
+    #[inline]
+    pub fn get_compression_threshold(&self) -> i32 {
+        self.compression_threshold
+    }
+
+    /// Set the zlib compression level of sent packets, from 0 (none) to 9 (best). Defaults to 6.
+    #[inline]
+    pub fn set_compression_level(&mut self, level: u32) {
+        self.dispatcher.set_compression_level(level);
+    }
+
+    /// Get the zlib compression level of sent packets.
+    #[inline]
+    pub fn get_compression_level(&self) -> u32 {
+        self.dispatcher.get_compression_level()
+    }
+
     /// Read and parse a packet from the internal `TcpStream`.
     #[inline]
     pub fn read_event(&mut self) -> TetsuResult<Event> {
+        let frame = self.frames.read_frame(&mut self.stream)?;
+        self.parse_frame(frame)
+    }
+
+    /// Read and parse a packet if a full one has been received, without blocking.
+    /// Partially received packets are kept until the rest arrives.
+    pub fn try_read_event(&mut self) -> TetsuResult<Option<Event>> {
+        self.stream.set_nonblocking(true)?;
+        let frame = self.frames.try_read_frame(&mut self.stream);
+        self.stream.set_nonblocking(false)?;
+
+        match frame? {
+            Some(frame) => Ok(Some(self.parse_frame(frame)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse a single length prefixed packet.
+    fn parse_frame(&self, frame: Vec<u8>) -> TetsuResult<Event> {
         self.dispatcher.read_event(
-            &mut self.stream,
+            &mut io::Cursor::new(frame),
             &self.state,
             &EventDirection::ClientBound,
             self.compression_threshold,
@@ -81,6 +161,43 @@ impl EncryptedConnection {
         )
     }
 
+    /// Read a packet without parsing it, returning its id and decompressed body.
+    pub fn read_raw(&mut self) -> TetsuResult<(i32, Vec<u8>)> {
+        let frame = self.frames.read_frame(&mut self.stream)?;
+        let mut bytes = &frame[..];
+        VarInt::read_from(&mut bytes)?;
+
+        let mut uncompressed;
+        if self.compression_threshold > 0 {
+            let uncompressed_len = VarInt::read_from(&mut bytes)?.0;
+
+            if uncompressed_len != 0 {
+                let uncompressed_len = checked_len(
+                    uncompressed_len as usize,
+                    MAX_PACKET_LEN,
+                    "Decompressed packet",
+                )?;
+                uncompressed = vec![0; uncompressed_len];
+                ZlibDecoder::new(bytes).read_exact(&mut uncompressed)?;
+                bytes = &uncompressed[..];
+            }
+        }
+
+        let id = VarInt::read_from(&mut bytes)?.0;
+        Ok((id, bytes.to_vec()))
+    }
+
+    /// Send a packet with any id and body, framed and compressed like any other event.
+    #[inline]
+    pub fn send_raw(&mut self, id: i32, body: &[u8]) -> TetsuResult<()> {
+        self.send_event(Event::Unknown {
+            id,
+            state: self.state,
+            direction: EventDirection::ServerBound,
+            data: body.to_vec(),
+        })
+    }
+
     /// Set the key to encrypt with.
     #[inline]
     pub fn set_cipher(&mut self, key: &[u8; 16]) -> TetsuResult<()> {
@@ -89,7 +206,7 @@ impl EncryptedConnection {
 
     /// Get the address of the internal `TcpStream`.
     #[inline]
-    pub fn get_address(&self) -> SocketAddr {
+    pub fn get_address(&self) -> TetsuResult<SocketAddr> {
         self.stream.get_address()
     }
 }
@@ -102,3 +219,317 @@ impl std::fmt::Debug for EncryptedConnection {
             .finish()
     }
 }
+
+/// Splits a byte stream into length prefixed packets.
+///
+/// Bytes are buffered across reads, so a packet can arrive in any number of
+/// pieces without being lost.
+#[derive(Debug, Default)]
+pub(crate) struct FrameReader {
+    /// Received bytes that don't make up a full packet yet.
+    buffer: Vec<u8>,
+}
+
+impl FrameReader {
+    /// Read a full packet, blocking until it arrives.
+    ///
+    /// `WouldBlock` is only returned if no part of the next packet has been
+    /// received, so read timeouts don't split a packet.
+    pub(crate) fn read_frame<R: Read>(&mut self, reader: &mut R) -> TetsuResult<Vec<u8>> {
+        loop {
+            if let Some(frame) = self.take_frame()? {
+                return Ok(frame);
+            }
+
+            match self.fill(reader) {
+                Err(Error::Io(e)) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(Error::Io(e))
+                    if e.kind() == io::ErrorKind::WouldBlock && !self.buffer.is_empty() =>
+                {
+                    continue
+                }
+                r => r?,
+            }
+        }
+    }
+
+    /// Read a full packet if one is available, returning `None` instead of blocking.
+    pub(crate) fn try_read_frame<R: Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> TetsuResult<Option<Vec<u8>>> {
+        loop {
+            if let Some(frame) = self.take_frame()? {
+                return Ok(Some(frame));
+            }
+
+            match self.fill(reader) {
+                Err(Error::Io(e)) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                r => r?,
+            }
+        }
+    }
+
+    /// Read whatever is available into the buffer.
+    fn fill<R: Read>(&mut self, reader: &mut R) -> TetsuResult<()> {
+        let mut chunk = [0; 4096];
+        let read = reader.read(&mut chunk)?;
+
+        if read == 0 {
+            return Err(Error::from(io::Error::from(io::ErrorKind::UnexpectedEof)));
+        }
+
+        self.buffer.extend_from_slice(&chunk[..read]);
+        Ok(())
+    }
+
+    /// Remove the first packet from the buffer if it has been fully received.
+    fn take_frame(&mut self) -> TetsuResult<Option<Vec<u8>>> {
+        let mut prefix = &self.buffer[..];
+
+        let len = match VarInt::read_from(&mut prefix) {
+            Ok(VarInt(len)) => checked_len(len as usize, MAX_PACKET_LEN, "Packet")?,
+            // Length prefix not fully received yet.
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let total = self.buffer.len() - prefix.len() + len;
+        if self.buffer.len() < total {
+            return Ok(None);
+        }
+
+        let rest = self.buffer.split_off(total);
+        Ok(Some(std::mem::replace(&mut self.buffer, rest)))
+    }
+}
+
+/// Log a user in on a freshly opened connection, leaving it in the Play state.
+pub(crate) fn perform_login(
+    connection: &mut EncryptedConnection,
+    user: &mojang::User,
+) -> TetsuResult<()> {
+    let start = Instant::now();
+
+    let (address, port) = match connection.get_address()? {
+        SocketAddr::V4(p) => (format!("{}", p.ip()), p.port()),
+        SocketAddr::V6(p) => (format!("{}", p.ip()), p.port()),
+    };
+
+    connection.set_state(&EventState::Handshake);
+
+    connection.send_event(Event::Handshake(Handshake {
+        protocol_version: None,
+        server_address: address,
+        server_port: port,
+        next_state: EventState::Login,
+    }))?;
+
+    connection.set_state(&EventState::Login);
+
+    connection.send_event(Event::LoginStart(LoginStart {
+        name: user.selected_profile.name.clone(),
+    }))?;
+
+    let mut encrypted = false;
+
+    loop {
+        match connection.read_event()? {
+            Event::EncryptionRequest(request) => {
+                let mut shared = [0; 16];
+                rand_bytes(&mut shared)?;
+
+                let pkey = Rsa::public_key_from_der(&request.public_key)?;
+
+                let response = EncryptionResponse {
+                    shared_secret: public_encrypt(&pkey, &shared)?,
+                    verify_token: public_encrypt(&pkey, &request.verify_token)?,
+                };
+
+                user.join_server(&request.server_id, &shared, &request.public_key)?;
+
+                connection.send_event(Event::EncryptionResponse(response))?;
+                connection.set_cipher(&shared)?;
+                encrypted = true;
+            }
+            Event::SetCompression(c) => connection.set_compression_threshold(c.threshold),
+            Event::LoginSuccess(_) => break,
+            Event::Disconnect(d) => {
+                return Err(Error::from(InvalidValue {
+                    expected: format!("LoginSuccess, disconnected: {:?}", d.reason),
+                }))
+            }
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "Login event".to_owned(),
+                }))
+            }
+        }
+    }
+
+    if !encrypted {
+        warn!("Server running in offline mode.");
+    }
+    info!("Login success at: {} ms!", start.elapsed().as_millis());
+
+    connection.set_state(&EventState::Play);
+
+    Ok(())
+}
+
+/// Async encrypted connection to a Minecraft server.
+///
+/// Frames are buffered in full before being decoded with the same
+/// [`dispatcher::EventDispatcher`] the blocking connection uses.
+#[cfg(feature = "tokio")]
+pub struct AsyncEncryptedConnection<S = tokio::net::TcpStream> {
+    /// Internal stream.
+    stream: AsyncEncryptedStream<S>,
+    /// Current connection state (Status/Handshake/Login/Play).
+    state: EventState,
+    /// Protocol version used by the connection.
+    pub protocol_version: ProtocolVersion,
+    /// Compression threshold.
+    compression_threshold: i32,
+    /// Internal event dispatcher.
+    dispatcher: dispatcher::EventDispatcher<io::Cursor<Vec<u8>>, Vec<u8>>,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncEncryptedConnection<tokio::net::TcpStream> {
+    /// Construct a new async Encrypted Connection to a server.
+    #[inline]
+    pub async fn new(
+        address: &str,
+        port: u16,
+        protocol_version: ProtocolVersion,
+    ) -> TetsuResult<Self> {
+        let stream = tokio::net::TcpStream::connect((address, port)).await?;
+        stream.set_nodelay(true)?;
+        Ok(Self::from_stream(stream, protocol_version))
+    }
+
+    /// Get the address of the internal `TcpStream`.
+    #[inline]
+    pub fn get_address(&self) -> TetsuResult<SocketAddr> {
+        Ok(self.stream.get_ref().peer_addr()?)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncEncryptedConnection<S> {
+    /// Construct a connection over an already connected stream.
+    #[inline]
+    pub fn from_stream(stream: S, protocol_version: ProtocolVersion) -> Self {
+        Self {
+            stream: AsyncEncryptedStream::new(stream),
+            state: EventState::Status,
+            protocol_version,
+            compression_threshold: 0,
+            dispatcher: dispatcher::EventDispatcher::new(&protocol_version),
+        }
+    }
+
+    /// Set the current state of the the connection.
+    #[inline]
+    pub fn set_state(&mut self, state: &EventState) {
+        info!(
+            "Switching connection state from {:?} -> {:?}",
+            self.state, state
+        );
+        self.state = *state;
+    }
+
+    /// Set the packet compression threshold.
+    #[inline]
+    pub fn set_compression_threshold(&mut self, compression_threshold: i32) {
+        self.compression_threshold = compression_threshold;
+    }
+
+    /// Get the packet compression threshold. Compression is disabled if this is not positive.
+    #[inline]
+    pub fn get_compression_threshold(&self) -> i32 {
+        self.compression_threshold
+    }
+
+    /// Set the zlib compression level of sent packets, from 0 (none) to 9 (best). Defaults to 6.
+    #[inline]
+    pub fn set_compression_level(&mut self, level: u32) {
+        self.dispatcher.set_compression_level(level);
+    }
+
+    /// Get the zlib compression level of sent packets.
+    #[inline]
+    pub fn get_compression_level(&self) -> u32 {
+        self.dispatcher.get_compression_level()
+    }
+
+    /// Set the key to encrypt with.
+    #[inline]
+    pub fn set_cipher(&mut self, key: &[u8; 16]) -> TetsuResult<()> {
+        self.stream.set_cipher(key)
+    }
+
+    /// Read and parse a packet from the internal stream.
+    pub async fn read_event(&mut self) -> TetsuResult<Event> {
+        let mut frame = Vec::with_capacity(5);
+
+        loop {
+            let byte = self.stream.read_u8().await?;
+            frame.push(byte);
+
+            if byte & 0x80 == 0 {
+                break;
+            }
+            if frame.len() == 5 {
+                return Err(Error::from(InvalidValue {
+                    expected: "VarInt of at most 5 bytes".to_owned(),
+                }));
+            }
+        }
+
+        let len = checked_len(
+            VarInt::read_from(&mut &frame[..])?.0 as usize,
+            MAX_PACKET_LEN,
+            "Packet",
+        )?;
+
+        let start = frame.len();
+        frame.resize(start + len, 0);
+        self.stream.read_exact(&mut frame[start..]).await?;
+
+        self.dispatcher.read_event(
+            &mut io::Cursor::new(frame),
+            &self.state,
+            &EventDirection::ClientBound,
+            self.compression_threshold,
+        )
+    }
+
+    /// Send a packet to the internal stream.
+    pub async fn send_event(&mut self, event: Event) -> TetsuResult<()> {
+        let mut frame = Vec::new();
+
+        self.dispatcher.write_event(
+            &mut frame,
+            event,
+            &self.state,
+            &EventDirection::ServerBound,
+            self.compression_threshold,
+        )?;
+
+        self.stream.write_all(&frame).await?;
+        Ok(self.stream.flush().await?)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<S> std::fmt::Debug for AsyncEncryptedConnection<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("")
+            .field(&self.state)
+            .field(&self.protocol_version)
+            .finish()
+    }
+}
@@ -34,7 +34,8 @@ use tetsu::client;
 let user = client::mojang::User::authenticate(
     "user@email".to_owned(),
     "user_password".to_owned(),
-);
+)
+.unwrap();
 
 let mut client = client::Client::new("127.0.0.1", None, None).unwrap();
 client.connect_user(user).unwrap();
@@ -44,28 +45,53 @@ loop {
     // ...
 }
 ```
+
+## Event handlers
+```no_run
+use tetsu::client;
+
+let mut client = client::Client::new("127.0.0.1", None, None).unwrap();
+client.connect_user(client::mojang::User::offline("tetsu".to_owned())).unwrap();
+
+client.on(|event| println!("{:?}", event));
+client.run_event_loop().unwrap();
+```
 */
-use crate::crypto;
 use crate::errors::*;
 use crate::event::*;
 
+use std::convert::TryFrom;
 use std::sync::Mutex;
 use std::time;
 
-use log::{info, warn};
-
 pub mod connection;
 pub mod mojang;
+pub mod proxy;
+pub mod state;
+
+pub use proxy::ProxyConfig;
 
 /// High level wrapper around a connection to a Minecraft server.
 pub struct Client {
     // Mutex here is for interior mutability ->
     // allows server methods such as `read_event` to be called without passing a mutable reference to self.
-    connection: Mutex<connection::EncryptedConnection>,
+    pub(crate) connection: Mutex<connection::EncryptedConnection>,
     connected_address: String,
     connected_user: Option<mojang::User>,
+    // Kept to open a new connection on `reconnect`.
+    host: String,
+    port: u16,
+    protocol: ProtocolVersion,
+    read_timeout: Option<time::Duration>,
+    proxy: Option<ProxyConfig>,
+    auto_keepalive: bool,
+    handlers: Vec<EventHandler>,
+    // Error hit by `read_available_events` after it had already read some events.
+    pending_error: Mutex<Option<Error>>,
 }
 
+type EventHandler = Box<dyn Fn(&Event) + Send + Sync>;
+
 impl Client {
     /// Constructs a new server object.
     /// The connection will use port `25565` if the `port` argument is `None`.
@@ -76,23 +102,19 @@ impl Client {
         port: Option<u16>,
         protocol: Option<ProtocolVersion>,
     ) -> Result<Self, Error> {
-        let port = match port {
-            Some(p) => p,
-            _ => 25565,
-        };
+        ClientBuilder {
+            address: Some(address.to_owned()),
+            port,
+            protocol,
+            ..Default::default()
+        }
+        .build()
+    }
 
-        Ok(Self {
-            connection: Mutex::new(connection::EncryptedConnection::new(
-                address,
-                port,
-                match protocol {
-                    Some(p) => p,
-                    _ => Self::get_server_version(&address, Some(port))?.protocol,
-                },
-            )?),
-            connected_address: format!("{}:{}", address, port),
-            connected_user: None,
-        })
+    /// Get a builder to configure a new client.
+    #[inline]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
     }
 
     /// Get the address with which the server was connected to,
@@ -103,8 +125,8 @@ impl Client {
 
     /// Get the ip address and port of the server.
     #[inline]
-    pub fn get_server_connection_address(&self) -> connection::SocketAddr {
-        self.connection.lock().unwrap().get_address()
+    pub fn get_server_connection_address(&self) -> TetsuResult<connection::SocketAddr> {
+        self.connection.lock()?.get_address()
     }
 
     /// Get the currently connected user.
@@ -113,162 +135,391 @@ impl Client {
         &self.connected_user
     }
 
+    /// Set the read timeout of the connection. Reads block indefinitely if `dur` is `None`.
+    #[inline]
+    pub fn set_read_timeout(&self, dur: Option<time::Duration>) -> TetsuResult<()> {
+        self.connection.lock()?.set_read_timeout(dur)?;
+        Ok(())
+    }
+
+    /// Get the packet compression threshold set by the server.
+    /// Compression is disabled if this is not positive.
+    #[inline]
+    pub fn get_compression_threshold(&self) -> TetsuResult<i32> {
+        Ok(self.connection.lock()?.get_compression_threshold())
+    }
+
+    /// Answer [`KeepAlive`] events automatically when they are read. Enabled by default.
+    #[inline]
+    pub fn enable_auto_keepalive(&mut self, enable: bool) {
+        self.auto_keepalive = enable;
+    }
+
     /// Read incoming server events.
     #[inline]
-    pub fn read_event(&self) -> Result<Event, ConnectionError<connection::EncryptedConnection>> {
-        Ok(self.connection.lock()?.read_event()?)
+    pub fn read_event(&self) -> TetsuResult<Event> {
+        let mut connection = self.connection.lock()?;
+        let event = connection.read_event()?;
+        self.handle_event(&mut connection, &event)?;
+        Ok(event)
     }
 
-    /// Send an event to the server.
+    /// Read incoming server events until one of type `T` is received, skipping all others.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use tetsu::client;
+    /// use tetsu::event::JoinGame;
+    ///
+    /// let client = client::Client::new("127.0.0.1", None, None).unwrap();
+    /// let join: JoinGame = client.read_event_of().unwrap();
+    /// ```
+    pub fn read_event_of<T: TryFrom<Event>>(&self) -> TetsuResult<T> {
+        loop {
+            if let Ok(event) = T::try_from(self.read_event()?) {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Read an incoming server event if one has been fully received, without blocking.
+    #[inline]
+    pub fn try_read_event(&self) -> TetsuResult<Option<Event>> {
+        self.try_read_locked(&mut *self.connection.lock()?)
+    }
+
+    /// Read every event that has been fully received, without blocking.
+    ///
+    /// If reading fails after some events have been read, those events are
+    /// returned and the error is returned by the next call instead.
+    pub fn read_available_events(&self) -> TetsuResult<Vec<Event>> {
+        if let Some(e) = self.pending_error.lock()?.take() {
+            return Err(e);
+        }
+
+        let mut connection = self.connection.lock()?;
+        let mut events = vec![];
+
+        loop {
+            match self.try_read_locked(&mut connection) {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => return Ok(events),
+                Err(e) if events.is_empty() => return Err(e),
+                Err(e) => {
+                    *self.pending_error.lock()? = Some(e);
+                    return Ok(events);
+                }
+            }
+        }
+    }
+
+    /// Read an event without blocking from an already locked connection.
+    fn try_read_locked(
+        &self,
+        connection: &mut connection::EncryptedConnection,
+    ) -> TetsuResult<Option<Event>> {
+        let event = connection.try_read_event()?;
+        if let Some(e) = &event {
+            self.handle_event(connection, e)?;
+        }
+        Ok(event)
+    }
+
+    /// Register a handler that is called with every event read by [`Client::run_event_loop`].
     #[inline]
-    pub fn send_event(
+    pub fn on<F: Fn(&Event) + Send + Sync + 'static>(&mut self, handler: F) {
+        self.handlers.push(Box::new(handler));
+    }
+
+    /// Read events and pass them to all registered handlers until the server disconnects.
+    pub fn run_event_loop(&self) -> TetsuResult<()> {
+        loop {
+            let event = self.read_event()?;
+
+            for handler in &self.handlers {
+                handler(&event);
+            }
+
+            if let Event::Disconnect(_) = event {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Respond to events the client handles itself.
+    fn handle_event(
         &self,
-        _event: Event,
-    ) -> Result<(), ConnectionError<connection::EncryptedConnection>> {
+        connection: &mut connection::EncryptedConnection,
+        event: &Event,
+    ) -> TetsuResult<()> {
+        if let (true, Event::KeepAlive(k)) = (self.auto_keepalive, event) {
+            connection.send_event(Event::KeepAliveResponse(KeepAliveResponse { id: k.id }))?;
+        }
+        Ok(())
+    }
+
+    /// Send an event to the server.
+    #[inline]
+    pub fn send_event(&self, _event: Event) -> TetsuResult<()> {
         self.connection.lock()?.send_event(_event)?;
         Ok(())
     }
 
     /// Attempt to get the protocol version of a server.
     pub fn get_server_version(address: &str, port: Option<u16>) -> Result<ServerVersion, Error> {
-        let port = match port {
-            Some(p) => p,
-            _ => 25565,
-        };
+        Ok(Self::request_status(address, port.unwrap_or(25565), None)?
+            .1
+            .version)
+    }
+
+    /// Ping a server, returning its information and the measured latency.
+    pub fn ping_server(
+        address: &str,
+        port: Option<u16>,
+    ) -> Result<(ServerInformation, time::Duration), Error> {
+        let (mut connection, information) =
+            Self::request_status(address, port.unwrap_or(25565), None)?;
+
+        let payload = time::SystemTime::now()
+            .duration_since(time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+
+        let start = time::Instant::now();
+
+        connection.send_event(Event::Ping(Ping { payload }))?;
 
-        let mut connection =
-            connection::EncryptedConnection::new(address, port, ProtocolVersion::V47)?;
+        match connection.read_event()? {
+            Event::Pong(p) if p.payload == payload => Ok((information, start.elapsed())),
+            _ => Err(Error::from(InvalidValue {
+                expected: format!("Pong with payload {}", payload),
+            })),
+        }
+    }
+
+    /// Open a status connection to a server and request its information.
+    fn request_status(
+        address: &str,
+        port: u16,
+        proxy: Option<ProxyConfig>,
+    ) -> Result<(connection::EncryptedConnection, ServerInformation), Error> {
+        let mut connection = match proxy {
+            Some(proxy) => connection::EncryptedConnection::new_via_proxy(
+                address,
+                port,
+                ProtocolVersion::V47,
+                proxy,
+            )?,
+            None => connection::EncryptedConnection::new(address, port, ProtocolVersion::V47)?,
+        };
 
         connection.set_state(&EventState::Handshake);
 
-        connection
-            .send_event(Event::Handshake(Handshake {
-                server_address: address.to_owned(),
-                server_port: port,
-                next_state: EventState::Status,
-            }))
-            .unwrap();
+        connection.send_event(Event::Handshake(Handshake {
+            protocol_version: None,
+            server_address: address.to_owned(),
+            server_port: port,
+            next_state: EventState::Status,
+        }))?;
 
         connection.set_state(&EventState::Status);
 
-        connection
-            .send_event(Event::StatusRequest(StatusRequest {}))
-            .unwrap();
+        connection.send_event(Event::StatusRequest(StatusRequest {}))?;
 
-        Ok(match connection.read_event()? {
-            Event::StatusResponse(e) => e.response.version,
-            _ => {
-                return Err(Error::from(InvalidValue {
-                    expected: "StatusResponse".to_owned(),
-                }))
-            }
-        })
+        match connection.read_event()? {
+            Event::StatusResponse(e) => Ok((connection, e.response)),
+            _ => Err(Error::from(InvalidValue {
+                expected: "StatusResponse".to_owned(),
+            })),
+        }
     }
 
     /// Connect a user to the server. Only one user can be connected at a time.
-    pub fn connect_user(
-        &mut self,
-        user: mojang::User,
-    ) -> Result<(), ConnectionError<connection::EncryptedConnection>> {
-        let start = time::Instant::now();
-
+    ///
+    /// Client settings aren't sent after logging in, so send a
+    /// [`ClientSettings`](crate::event::ClientSettings) event if the server
+    /// expects one.
+    pub fn connect_user(&mut self, user: mojang::User) -> TetsuResult<()> {
         if let Some(p) = &self.connected_user {
-            return Err(ConnectionError::from(Error::from(InvalidValue {
+            return Err(Error::from(InvalidValue {
                 expected: format!("User {} already connected.", p.selected_profile.name),
-            })));
+            }));
         }
 
-        let (address, port) = match self.get_server_connection_address() {
-            connection::SocketAddr::V4(p) => (format!("{}", p.ip()), p.port()),
-            connection::SocketAddr::V6(p) => (format!("{}", p.ip()), p.port()),
-        };
-
-        self.connection.lock()?.set_state(&EventState::Handshake);
-
-        self.connection
-            .lock()?
-            .send_event(Event::Handshake(Handshake {
-                server_address: address,
-                server_port: port,
-                next_state: EventState::Login,
-            }))
-            .unwrap();
-
-        self.connection.lock()?.set_state(&EventState::Login);
-
-        self.connection
-            .lock()?
-            .send_event(Event::LoginStart(LoginStart {
-                name: user.selected_profile.name.clone(),
-            }))
-            .unwrap();
-
-        let encryption_request = match self.connection.lock().unwrap().read_event()? {
-            Event::EncryptionRequest(e) => e,
-            Event::LoginSuccess(_) => {
-                warn!("Server running in offline mode. Logging in.");
-                info!("Login success at: {} ms!", start.elapsed().as_millis());
-                self.connected_user = Some(user);
-                self.connection.lock()?.set_state(&EventState::Play);
-                return Ok(());
-            }
-            _ => {
-                return Err(ConnectionError::from(Error::from(InvalidValue {
-                    expected: "EncryptionRequest".to_owned(),
-                })))
-            }
-        };
+        connection::perform_login(&mut *self.connection.lock()?, &user)?;
+        self.connected_user = Some(user);
 
-        let mut encryption_response = EncryptionResponse {
-            shared_secret: vec![],
-            verify_token: vec![],
-        };
+        Ok(())
+    }
 
-        let mut shared = [0; 16];
+    /// Drop the current connection and open a new one to the same server,
+    /// logging the connected user back in if there is one.
+    ///
+    /// The current connection is kept if the new one can't be opened or the login fails.
+    pub fn reconnect(&mut self) -> TetsuResult<()> {
+        let mut new_connection = open_connection(
+            &self.host,
+            self.port,
+            self.protocol,
+            self.proxy.clone(),
+            self.read_timeout,
+        )?;
+
+        if let Some(user) = &self.connected_user {
+            connection::perform_login(&mut new_connection, user)?;
+        }
 
-        {
-            crypto::rand_bytes(&mut shared)?;
+        *self.connection.get_mut()? = new_connection;
+        // Errors from the old connection don't apply to the new one.
+        *self.pending_error.get_mut()? = None;
+        Ok(())
+    }
+}
 
-            let pkey = crypto::Rsa::public_key_from_der(&encryption_request.public_key)?;
+/// Open a connection to a server, optionally through a proxy.
+fn open_connection(
+    address: &str,
+    port: u16,
+    protocol: ProtocolVersion,
+    proxy: Option<ProxyConfig>,
+    read_timeout: Option<time::Duration>,
+) -> TetsuResult<connection::EncryptedConnection> {
+    let mut connection = match proxy {
+        Some(proxy) => {
+            connection::EncryptedConnection::new_via_proxy(address, port, protocol, proxy)?
+        }
+        None => connection::EncryptedConnection::new(address, port, protocol)?,
+    };
+    connection.set_read_timeout(read_timeout)?;
+    Ok(connection)
+}
 
-            encryption_response.shared_secret = crypto::public_encrypt(&pkey, &shared)?;
-            encryption_response.verify_token =
-                crypto::public_encrypt(&pkey, &encryption_request.verify_token)?;
+/// Builder for a [`Client`].
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use tetsu::client::ClientBuilder;
+/// use tetsu::event::ProtocolVersion;
+///
+/// let client = ClientBuilder::new()
+///     .address("127.0.0.1")
+///     .protocol(ProtocolVersion::V47)
+///     .read_timeout(Duration::from_secs(30))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    address: Option<String>,
+    port: Option<u16>,
+    protocol: Option<ProtocolVersion>,
+    read_timeout: Option<time::Duration>,
+    auto_keepalive: bool,
+    proxy: Option<ProxyConfig>,
+}
 
-            user.join_server(
-                &encryption_request.server_id,
-                &shared,
-                &encryption_request.public_key,
-            );
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            address: None,
+            port: None,
+            protocol: None,
+            read_timeout: None,
+            auto_keepalive: true,
+            proxy: None,
         }
+    }
+}
 
-        self.connection
-            .lock()?
-            .send_event(Event::EncryptionResponse(encryption_response))
-            .unwrap();
+impl ClientBuilder {
+    /// Constructs a new builder with default options.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        self.connection.lock()?.set_cipher(&shared)?;
+    /// Set the address of the server. This is required.
+    #[inline]
+    pub fn address(mut self, address: &str) -> Self {
+        self.address = Some(address.to_owned());
+        self
+    }
 
-        loop {
-            let event = self.connection.lock()?.read_event()?;
-            match event {
-                Event::SetCompression(c) => self
-                    .connection
-                    .lock()
-                    .unwrap()
-                    .set_compression_threshold(c.threshold),
-                Event::LoginSuccess(_) => {
-                    info!("Login success at: {} ms!", start.elapsed().as_millis());
-                    self.connected_user = Some(user);
-                    break;
-                }
-                Event::Disconnect(c) => panic!("Disconnected!: {:?}", c),
-                _ => panic!("Unknown event!"),
-            };
-        }
+    /// Set the port of the server. Defaults to `25565`.
+    #[inline]
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
 
-        self.connection.lock()?.set_state(&EventState::Play);
+    /// Set the protocol version. It will be auto-detected if this isn't set.
+    #[inline]
+    pub fn protocol(mut self, protocol: ProtocolVersion) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
 
-        Ok(())
+    /// Set the read timeout of the connection. Reads block indefinitely by default.
+    #[inline]
+    pub fn read_timeout(mut self, dur: time::Duration) -> Self {
+        self.read_timeout = Some(dur);
+        self
+    }
+
+    /// Answer [`KeepAlive`] events automatically when they are read. Enabled by default.
+    #[inline]
+    pub fn auto_keepalive(mut self, enable: bool) -> Self {
+        self.auto_keepalive = enable;
+        self
+    }
+
+    /// Connect to the server through a SOCKS5 proxy.
+    #[inline]
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Connect to the server and construct the client.
+    pub fn build(self) -> TetsuResult<Client> {
+        let address = self.address.ok_or_else(|| {
+            Error::from(InvalidValue {
+                expected: "server address".to_owned(),
+            })
+        })?;
+        let port = self.port.unwrap_or(25565);
+
+        let protocol = match self.protocol {
+            Some(p) => p,
+            _ => {
+                Client::request_status(&address, port, self.proxy.clone())?
+                    .1
+                    .version
+                    .protocol
+            }
+        };
+
+        let connection = open_connection(
+            &address,
+            port,
+            protocol,
+            self.proxy.clone(),
+            self.read_timeout,
+        )?;
+
+        Ok(Client {
+            connection: Mutex::new(connection),
+            connected_address: format!("{}:{}", address, port),
+            connected_user: None,
+            host: address,
+            port,
+            protocol,
+            read_timeout: self.read_timeout,
+            proxy: self.proxy,
+            auto_keepalive: self.auto_keepalive,
+            handlers: vec![],
+            pending_error: Mutex::new(None),
+        })
     }
 }
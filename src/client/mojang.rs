@@ -1,13 +1,26 @@
 //! Mojang user information.
 
 use crate::crypto;
+use crate::errors::*;
 
+use md5::{Digest, Md5};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 /// Mojang authentication server.
 pub const AUTH_SERVER: &str = "https://authserver.mojang.com/authenticate";
 /// Server join request session-server.
 pub const JOIN_SERVER: &str = "https://sessionserver.mojang.com/session/minecraft/join";
+/// Xbox Live user authentication server.
+pub const XBL_AUTH_SERVER: &str = "https://user.auth.xboxlive.com/user/authenticate";
+/// Xbox Live security token server.
+pub const XSTS_AUTH_SERVER: &str = "https://xsts.auth.xboxlive.com/xsts/authorize";
+/// Minecraft services Xbox Live login.
+pub const XBOX_LOGIN_SERVER: &str =
+    "https://api.minecraftservices.com/authentication/login_with_xbox";
+/// Minecraft services profile of the logged in user.
+pub const PROFILE_SERVER: &str = "https://api.minecraftservices.com/minecraft/profile";
 
 // ----- Mojang sent info -----
 
@@ -32,7 +45,7 @@ pub struct UserInfo {
 }
 
 /// A single user profile.
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct UserProfile {
     /// Profile's username.
     pub name: String,
@@ -85,6 +98,100 @@ struct JoinServer {
     pub server_id: String,
 }
 
+// ----- Microsoft auth ------
+
+/// Properties of an Xbox Live authentication request.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct XblProperties {
+    auth_method: String,
+    site_name: String,
+    rps_ticket: String,
+}
+
+/// Properties of an XSTS authorization request.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct XstsProperties {
+    sandbox_id: String,
+    user_tokens: Vec<String>,
+}
+
+/// Information required by the Xbox Live and XSTS servers.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct XboxAuthentication<P> {
+    properties: P,
+    relying_party: String,
+    token_type: String,
+}
+
+/// User hash sent by Xbox Live.
+#[derive(Serialize, Deserialize, Debug)]
+struct XboxUserHash {
+    uhs: String,
+}
+
+/// Claims sent by Xbox Live.
+#[derive(Serialize, Deserialize, Debug)]
+struct XboxDisplayClaims {
+    xui: Vec<XboxUserHash>,
+}
+
+/// Token sent by the Xbox Live and XSTS servers.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct XboxToken {
+    token: String,
+    display_claims: XboxDisplayClaims,
+}
+
+/// Information required by /login_with_xbox.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct XboxLogin {
+    identity_token: String,
+}
+
+/// Minecraft services access token.
+#[derive(Serialize, Deserialize, Debug)]
+struct MinecraftToken {
+    access_token: String,
+}
+
+/// Send a JSON request and parse the JSON response.
+fn post_json<S: Serialize, D: DeserializeOwned>(url: &str, body: &S) -> Result<D, Error> {
+    let res = ureq::post(url)
+        .set("content-type", "application/json")
+        .set("accept", "application/json")
+        .send_string(&serde_json::to_string(body)?);
+
+    Ok(serde_json::from_str(&read_response(res)?)?)
+}
+
+/// Get the body of a response, or an [`Error::Auth`] if the request failed.
+pub(crate) fn read_response(res: Result<ureq::Response, ureq::Error>) -> Result<String, Error> {
+    Ok(check_response(res)?.into_string()?)
+}
+
+/// Get the status code of a response, or an [`Error::Auth`] if the request failed.
+fn read_status(res: Result<ureq::Response, ureq::Error>) -> Result<u16, Error> {
+    Ok(check_response(res)?.status())
+}
+
+/// Convert a failed request into an [`Error::Auth`].
+fn check_response(res: Result<ureq::Response, ureq::Error>) -> Result<ureq::Response, Error> {
+    match res {
+        Ok(r) => Ok(r),
+        Err(ureq::Error::Status(code, response)) => Err(Error::Auth(format!(
+            "[{}] bad-status: {}",
+            code,
+            response.into_string().unwrap_or_default()
+        ))),
+        Err(e) => Err(Error::Auth(format!("transport error: {}", e))),
+    }
+}
+
 // ------ Mojang Profile ------
 
 /// Structure representing a Mojang user.
@@ -106,63 +213,143 @@ pub struct User {
 impl User {
     /// Authenticate with the Mojang authentication servers.
     /// Returns a new User.
-    pub fn authenticate(username: String, password: String) -> Self {
+    pub fn authenticate(username: String, password: String) -> Result<Self, Error> {
         let user_auth = UserAuthentication {
             agent: Default::default(),
             username,
             password,
             client_token: String::from("dufc231fhufbcuibeacda42323dsc"),
         };
-        let auth_request = serde_json::to_string(&user_auth).unwrap();
-        let res = ureq::post(AUTH_SERVER)
-            .set("content-type", "application/json")
-            .send_string(&auth_request[..]);
-
-        let res = match res {
-            Ok(r) => r,
-            Err(ureq::Error::Status(code, response)) => {
-                panic!(
-                    "Failed to log in [[{}] bad-status]: {}",
-                    code,
-                    response.into_string().unwrap()
+
+        post_json(AUTH_SERVER, &user_auth)
+    }
+
+    /// Authenticate with a Microsoft account through Xbox Live.
+    /// `ms_access_token` is an OAuth access token for the `XboxLive.signin` scope.
+    /// Returns a new User.
+    pub fn authenticate_microsoft(ms_access_token: String) -> Result<Self, Error> {
+        let xbl: XboxToken = post_json(
+            XBL_AUTH_SERVER,
+            &XboxAuthentication {
+                properties: XblProperties {
+                    auth_method: "RPS".to_owned(),
+                    site_name: "user.auth.xboxlive.com".to_owned(),
+                    rps_ticket: format!("d={}", ms_access_token),
+                },
+                relying_party: "http://auth.xboxlive.com".to_owned(),
+                token_type: "JWT".to_owned(),
+            },
+        )?;
+
+        let xsts: XboxToken = post_json(
+            XSTS_AUTH_SERVER,
+            &XboxAuthentication {
+                properties: XstsProperties {
+                    sandbox_id: "RETAIL".to_owned(),
+                    user_tokens: vec![xbl.token],
+                },
+                relying_party: "rp://api.minecraftservices.com/".to_owned(),
+                token_type: "JWT".to_owned(),
+            },
+        )?;
+
+        let user_hash = match xsts.display_claims.xui.first() {
+            Some(x) => &x.uhs,
+            _ => return Err(Error::Auth("XSTS response has no user hash".to_owned())),
+        };
+
+        let minecraft: MinecraftToken = post_json(
+            XBOX_LOGIN_SERVER,
+            &XboxLogin {
+                identity_token: format!("XBL3.0 x={};{}", user_hash, xsts.token),
+            },
+        )?;
+
+        let profile: UserProfile = serde_json::from_str(&read_response(
+            ureq::get(PROFILE_SERVER)
+                .set(
+                    "authorization",
+                    &format!("Bearer {}", minecraft.access_token),
                 )
-            }
-            _ => panic!("Failed to log in [unknown-response]"),
+                .call(),
+        )?)?;
+
+        Ok(Self {
+            user: None,
+            client_token: String::new(),
+            access_token: minecraft.access_token,
+            available_profiles: vec![profile.clone()],
+            selected_profile: profile,
+        })
+    }
+
+    /// Create a user for servers running in offline mode.
+    /// The profile UUID is derived from the name the same way vanilla servers do.
+    pub fn offline(username: String) -> Self {
+        let profile = UserProfile {
+            id: Self::offline_uuid(&username).to_simple().to_string(),
+            name: username,
         };
 
-        let res = res.into_string().unwrap();
+        Self {
+            user: None,
+            client_token: String::new(),
+            access_token: String::new(),
+            available_profiles: vec![profile.clone()],
+            selected_profile: profile,
+        }
+    }
+
+    /// Get the UUID an offline mode server assigns to `username`.
+    ///
+    /// This is a version 3 (MD5) UUID of `"OfflinePlayer:" + username`,
+    /// matching Java's `UUID.nameUUIDFromBytes`.
+    pub fn offline_uuid(username: &str) -> Uuid {
+        let mut bytes: [u8; 16] =
+            Md5::digest(format!("OfflinePlayer:{}", username).as_bytes()).into();
 
-        serde_json::from_str(&res[..]).unwrap()
+        bytes[6] = (bytes[6] & 0x0F) | 0x30;
+        bytes[8] = (bytes[8] & 0x3F) | 0x80;
+
+        Uuid::from_bytes(bytes)
     }
 
-    /// Send a server join request to Mojang.
-    pub fn join_server(&self, server_id: &str, shared_key: &[u8], public_key: &[u8]) {
-        let hash_str;
-        {
-            let mut hasher = crypto::Sha1::new();
-            hasher.update(server_id.as_bytes());
-            hasher.update(shared_key);
-            hasher.update(public_key);
+    /// Check if this user was created with [`User::offline`].
+    #[inline]
+    pub fn is_offline(&self) -> bool {
+        self.access_token.is_empty()
+    }
 
-            hash_str = crypto::hexdigest(hasher);
+    /// Send a server join request to Mojang. Does nothing for offline users.
+    pub fn join_server(
+        &self,
+        server_id: &str,
+        shared_key: &[u8],
+        public_key: &[u8],
+    ) -> Result<(), Error> {
+        if self.is_offline() {
+            return Ok(());
         }
 
         let join_info = JoinServer {
             access_token: self.access_token.clone(),
             selected_profile: self.selected_profile.id.clone(),
-            server_id: hash_str,
+            server_id: crypto::server_hash(server_id, shared_key, public_key),
         };
 
-        let res = ureq::post(JOIN_SERVER)
-            .set("content-type", "application/json")
-            .send_string(&serde_json::to_string(&join_info).unwrap()[..])
-            .unwrap();
+        let res = read_status(
+            ureq::post(JOIN_SERVER)
+                .set("content-type", "application/json")
+                .send_string(&serde_json::to_string(&join_info)?),
+        )?;
 
-        if res.status() != 204 {
-            panic!(
+        if res != 204 {
+            return Err(Error::Auth(format!(
                 "Failed to authenticate with server [[{}] bad-status].",
-                res.status()
-            );
+                res
+            )));
         }
+
+        Ok(())
     }
 }
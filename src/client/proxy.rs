@@ -0,0 +1,143 @@
+//! SOCKS5 proxy support.
+//!
+//! See [RFC 1928](https://datatracker.ietf.org/doc/html/rfc1928).
+
+use crate::errors::{Error, InvalidValue, TetsuResult};
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpStream};
+
+/// SOCKS5 proxy to tunnel a connection through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Address of the proxy, as `host:port`.
+    pub address: String,
+    /// Username and password, if the proxy requires authentication.
+    pub credentials: Option<(String, String)>,
+}
+
+impl ProxyConfig {
+    /// Constructs a proxy configuration without authentication.
+    #[inline]
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_owned(),
+            credentials: None,
+        }
+    }
+
+    /// Authenticate with a username and password.
+    #[inline]
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.to_owned(), password.to_owned()));
+        self
+    }
+}
+
+const SOCKS_VERSION: u8 = 0x05;
+const SOCKS_NO_AUTH: u8 = 0x00;
+const SOCKS_PASSWORD_AUTH: u8 = 0x02;
+const SOCKS_CONNECT: u8 = 0x01;
+const SOCKS_IPV4: u8 = 0x01;
+const SOCKS_DOMAIN: u8 = 0x03;
+const SOCKS_IPV6: u8 = 0x04;
+
+/// Open a TCP connection to the `target` address, tunneled through a SOCKS5 proxy.
+pub fn connect(target: &str, proxy: &ProxyConfig) -> TetsuResult<TcpStream> {
+    let mut stream = TcpStream::connect(&proxy.address)?;
+    socks5_connect(&mut stream, target, proxy)?;
+    Ok(stream)
+}
+
+/// Ask a SOCKS5 proxy to open a tunnel to `target`.
+fn socks5_connect(stream: &mut TcpStream, target: &str, proxy: &ProxyConfig) -> TetsuResult<()> {
+    let invalid = |expected: &str| {
+        Error::from(InvalidValue {
+            expected: expected.to_owned(),
+        })
+    };
+
+    let (host, port) = match target.rsplit_once(':') {
+        Some((host, port)) => (
+            host.trim_start_matches('[').trim_end_matches(']'),
+            port.parse::<u16>()
+                .map_err(|_| invalid("target address as host:port"))?,
+        ),
+        None => return Err(invalid("target address as host:port")),
+    };
+
+    // Negotiate an authentication method.
+    let method = match proxy.credentials {
+        Some(_) => SOCKS_PASSWORD_AUTH,
+        None => SOCKS_NO_AUTH,
+    };
+    stream.write_all(&[SOCKS_VERSION, 1, method])?;
+
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply)?;
+    if reply != [SOCKS_VERSION, method] {
+        return Err(invalid("proxy to accept the authentication method"));
+    }
+
+    // Username/password authentication, RFC 1929.
+    if let Some((username, password)) = &proxy.credentials {
+        if username.len() > 255 || password.len() > 255 {
+            return Err(invalid("proxy credentials of at most 255 bytes"));
+        }
+
+        let mut request = vec![0x01, username.len() as u8];
+        request.extend_from_slice(username.as_bytes());
+        request.push(password.len() as u8);
+        request.extend_from_slice(password.as_bytes());
+        stream.write_all(&request)?;
+
+        stream.read_exact(&mut reply)?;
+        if reply[1] != 0x00 {
+            return Err(invalid("proxy to accept the credentials"));
+        }
+    }
+
+    let mut request = vec![SOCKS_VERSION, SOCKS_CONNECT, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(SOCKS_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(SOCKS_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            if host.len() > 255 {
+                return Err(invalid("target host name of at most 255 bytes"));
+            }
+            request.push(SOCKS_DOMAIN);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut header = [0; 4];
+    stream.read_exact(&mut header)?;
+    if header[0] != SOCKS_VERSION || header[1] != 0x00 {
+        return Err(invalid("proxy to open the connection"));
+    }
+
+    // Skip the address the proxy bound to.
+    let address_len = match header[3] {
+        SOCKS_IPV4 => 4,
+        SOCKS_IPV6 => 16,
+        SOCKS_DOMAIN => {
+            let mut len = [0];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        _ => return Err(invalid("proxy bound address type")),
+    };
+    let mut bound = vec![0; address_len + 2];
+    stream.read_exact(&mut bound)?;
+
+    Ok(())
+}
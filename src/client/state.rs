@@ -0,0 +1,111 @@
+//! Game state tracked from server events.
+
+use crate::event::*;
+
+/// Keeps track of the player's gamemode, the weather and the player's abilities
+/// from the events the server sends.
+///
+/// # Examples
+/// ```no_run
+/// use tetsu::client;
+/// use tetsu::client::state::GameStateTracker;
+///
+/// let client = client::Client::new("127.0.0.1", None, None).unwrap();
+/// let mut state = GameStateTracker::new();
+///
+/// loop {
+///     state.update(&client.read_event().unwrap());
+///     if state.is_raining() {
+///         // ...
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GameStateTracker {
+    gamemode: Option<Gamemode>,
+    raining: bool,
+    rain_level: f32,
+    thunder_level: f32,
+    abilities: Option<PlayerAbility>,
+}
+
+impl GameStateTracker {
+    /// Constructs a tracker that hasn't seen any events.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the state from an event. Events that don't affect it are ignored.
+    pub fn update(&mut self, event: &Event) {
+        match event {
+            Event::JoinGame(e) => {
+                self.gamemode = Some(e.gamemode.clone());
+                self.clear_weather();
+            }
+            // Weather isn't carried over to the dimension being spawned into.
+            Event::Respawn(e) => {
+                self.gamemode = Some(e.gamemode.clone());
+                self.clear_weather();
+            }
+            Event::ChangeGameState(ChangeGameState::GamemodeUpdate(g)) => {
+                self.gamemode = Some(g.clone())
+            }
+            Event::ChangeGameState(ChangeGameState::BeginRaining) => self.raining = true,
+            Event::ChangeGameState(ChangeGameState::EndRaining) => self.raining = false,
+            Event::ChangeGameState(ChangeGameState::RainLevel(l)) => self.rain_level = *l,
+            Event::ChangeGameState(ChangeGameState::ThunderLevel(l)) => self.thunder_level = *l,
+            Event::PlayerAbility(a) => self.abilities = Some(a.clone()),
+            _ => {}
+        }
+    }
+
+    #[inline]
+    fn clear_weather(&mut self) {
+        self.raining = false;
+        self.rain_level = 0.0;
+        self.thunder_level = 0.0;
+    }
+
+    /// Get the player's gamemode, if it has been received.
+    #[inline]
+    pub fn gamemode(&self) -> Option<&Gamemode> {
+        self.gamemode.as_ref()
+    }
+
+    /// Check if it is raining.
+    #[inline]
+    pub fn is_raining(&self) -> bool {
+        self.raining
+    }
+
+    /// Get the strength of the rain, from `0.0` to `1.0`.
+    #[inline]
+    pub fn rain_level(&self) -> f32 {
+        self.rain_level
+    }
+
+    /// Get the strength of the thunder, from `0.0` to `1.0`.
+    #[inline]
+    pub fn thunder_level(&self) -> f32 {
+        self.thunder_level
+    }
+
+    /// Get the player's last received abilities.
+    #[inline]
+    pub fn abilities(&self) -> Option<&PlayerAbility> {
+        self.abilities.as_ref()
+    }
+
+    /// Check if the player is flying.
+    #[inline]
+    pub fn is_flying(&self) -> bool {
+        self.abilities.as_ref().is_some_and(|a| a.is_flying)
+    }
+
+    /// Check if the player can't take damage.
+    #[inline]
+    pub fn is_invulnerable(&self) -> bool {
+        self.abilities.as_ref().is_some_and(|a| a.invulnerable)
+    }
+}
@@ -1,42 +1,176 @@
 //! Commonly used cryptographic functions.
 
-use crate::errors::TetsuResult;
+use crate::errors::{Error, InvalidValue, TetsuResult};
 
 use std::io;
 pub use std::net::SocketAddr;
 use std::net::TcpStream;
+use std::time::Duration;
 
-use openssl::rsa::Padding;
+#[cfg(feature = "tokio")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 
-pub use openssl::pkey::{Private as PrivateKey, Public as PublicKey};
-pub use openssl::rand::rand_bytes;
-pub use openssl::rsa::Rsa;
-pub use openssl::sha::Sha1;
+pub use sha1::{Digest, Sha1};
+
+#[cfg(not(any(feature = "openssl", feature = "rustcrypto")))]
+compile_error!("either the `openssl` or the `rustcrypto` feature must be enabled");
+
+#[cfg(all(feature = "openssl", not(feature = "rustcrypto")))]
+pub use self::openssl_rsa::*;
+#[cfg(feature = "rustcrypto")]
+pub use self::rustcrypto_rsa::*;
 
 use aes::Aes128;
 use cfb8::cipher::{AsyncStreamCipher, NewCipher};
 use cfb8::Cfb8;
 
-/// Encrypt some data with an RSA public key.
-pub fn public_encrypt(key: &Rsa<PublicKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
-    // Not sure about this.
-    let mut decrypted = vec![0; 512];
-    let len = key.public_encrypt(&data, &mut decrypted, Padding::PKCS1)?;
-    Ok(decrypted[..len].to_vec())
+// All RSA operations use PKCS#1 v1.5 padding, as the protocol does. Data must
+// be at least 11 bytes shorter than the key.
+
+/// RSA implemented with OpenSSL.
+#[cfg(all(feature = "openssl", not(feature = "rustcrypto")))]
+mod openssl_rsa {
+    use crate::errors::TetsuResult;
+
+    use openssl::rsa::Padding;
+
+    pub use openssl::pkey::{Private as PrivateKey, Public as PublicKey};
+    pub use openssl::rand::rand_bytes;
+    pub use openssl::rsa::Rsa;
+
+    /// Encrypt some data with an RSA public key.
+    pub fn public_encrypt(key: &Rsa<PublicKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
+        let mut encrypted = vec![0; key.size() as usize];
+        let len = key.public_encrypt(data, &mut encrypted, Padding::PKCS1)?;
+        Ok(encrypted[..len].to_vec())
+    }
+
+    /// Decrypt data encrypted with the matching RSA public key.
+    pub fn rsa_private_decrypt(key: &Rsa<PrivateKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
+        let mut decrypted = vec![0; key.size() as usize];
+        let len = key.private_decrypt(data, &mut decrypted, Padding::PKCS1)?;
+        Ok(decrypted[..len].to_vec())
+    }
+
+    /// Encrypt some data with an RSA private key, so that it can be decrypted
+    /// with the matching public key.
+    pub fn rsa_private_encrypt(key: &Rsa<PrivateKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
+        let mut encrypted = vec![0; key.size() as usize];
+        let len = key.private_encrypt(data, &mut encrypted, Padding::PKCS1)?;
+        Ok(encrypted[..len].to_vec())
+    }
+}
+
+/// RSA implemented with the pure Rust `rsa` crate, mirroring the parts of
+/// OpenSSL's API used by the OpenSSL backend.
+#[cfg(feature = "rustcrypto")]
+mod rustcrypto_rsa {
+    use crate::errors::{Error, InvalidValue, TetsuResult};
+
+    use std::io;
+
+    use rand::rngs::OsRng;
+    use rand::RngCore;
+    use rsa::pkcs8::{DecodePublicKey, EncodePublicKey};
+    use rsa::traits::PublicKeyParts;
+    use rsa::{Pkcs1v15Encrypt, Pkcs1v15Sign};
+
+    pub use rsa::{RsaPrivateKey as PrivateKey, RsaPublicKey as PublicKey};
+
+    /// An RSA key.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Rsa<T>(T);
+
+    impl Rsa<PublicKey> {
+        /// Read a DER encoded SubjectPublicKeyInfo, as sent in an `EncryptionRequest`.
+        pub fn public_key_from_der(der: &[u8]) -> TetsuResult<Self> {
+            Ok(Self(PublicKey::from_public_key_der(der).map_err(|e| {
+                Error::from(InvalidValue {
+                    expected: format!("DER encoded RSA public key ({})", e),
+                })
+            })?))
+        }
+
+        /// Size of the key's modulus in bytes.
+        #[inline]
+        pub fn size(&self) -> u32 {
+            self.0.size() as u32
+        }
+    }
+
+    impl Rsa<PrivateKey> {
+        /// Generate a new key pair with a modulus of `bits` bits.
+        pub fn generate(bits: u32) -> TetsuResult<Self> {
+            Ok(Self(PrivateKey::new(&mut OsRng, bits as usize)?))
+        }
+
+        /// Encode the public half of the key as a DER SubjectPublicKeyInfo.
+        pub fn public_key_to_der(&self) -> TetsuResult<Vec<u8>> {
+            let der = self.0.to_public_key().to_public_key_der().map_err(|e| {
+                Error::from(InvalidValue {
+                    expected: format!("encodable RSA public key ({})", e),
+                })
+            })?;
+            Ok(der.into_vec())
+        }
+
+        /// Size of the key's modulus in bytes.
+        #[inline]
+        pub fn size(&self) -> u32 {
+            self.0.size() as u32
+        }
+    }
+
+    /// Fill `buf` with cryptographically secure random bytes.
+    pub fn rand_bytes(buf: &mut [u8]) -> TetsuResult<()> {
+        OsRng
+            .try_fill_bytes(buf)
+            .map_err(|e| Error::from(io::Error::from(e)))
+    }
+
+    /// Encrypt some data with an RSA public key.
+    pub fn public_encrypt(key: &Rsa<PublicKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
+        Ok(key.0.encrypt(&mut OsRng, Pkcs1v15Encrypt, data)?)
+    }
+
+    /// Decrypt data encrypted with the matching RSA public key.
+    pub fn rsa_private_decrypt(key: &Rsa<PrivateKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
+        Ok(key.0.decrypt(Pkcs1v15Encrypt, data)?)
+    }
+
+    /// Encrypt some data with an RSA private key, so that it can be decrypted
+    /// with the matching public key.
+    pub fn rsa_private_encrypt(key: &Rsa<PrivateKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
+        Ok(key.0.sign(Pkcs1v15Sign::new_unprefixed(), data)?)
+    }
 }
 
-/// Encrypt some data with an RSA private key.
-pub fn private_encrypt(key: &Rsa<PrivateKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
-    let mut decrypted = vec![0; 512];
-    let len = key.private_decrypt(&data, &mut decrypted, Padding::PKCS1)?;
-    Ok(decrypted[..len].to_vec())
+/// Decrypt the shared secret of an `EncryptionResponse` with the server's private key.
+/// The secret must be a 16 byte AES key.
+pub fn decrypt_shared_secret(private: &Rsa<PrivateKey>, encrypted: &[u8]) -> TetsuResult<Vec<u8>> {
+    let secret = rsa_private_decrypt(private, encrypted)?;
+
+    if secret.len() != 16 {
+        return Err(Error::from(InvalidValue {
+            expected: format!("16 byte shared secret, got {} bytes", secret.len()),
+        }));
+    }
+    Ok(secret)
 }
 
-/// Decrypt some data with an RSA private key.
-pub fn private_decrypt(key: &Rsa<PrivateKey>, data: &[u8]) -> TetsuResult<Vec<u8>> {
-    let mut decrypted = vec![0; 512];
-    let len = key.private_encrypt(&data, &mut decrypted, Padding::PKCS1)?;
-    Ok(decrypted[..len].to_vec())
+/// Check that the verify token of an `EncryptionResponse` decrypts to the one
+/// sent in the `EncryptionRequest`.
+pub fn verify_token_matches(
+    private: &Rsa<PrivateKey>,
+    encrypted: &[u8],
+    expected: &[u8],
+) -> TetsuResult<bool> {
+    Ok(rsa_private_decrypt(private, encrypted)? == expected)
 }
 
 /// Default protocol stream cipher. Uses AES/CFB8.
@@ -65,6 +199,15 @@ impl DefaultStreamCipher {
     pub fn encrypt(&mut self, data: &mut [u8]) {
         self.cipher.encrypt(data)
     }
+
+    /// Encrypt `data` into `out`, replacing its contents.
+    /// `out` keeps its allocation, so it can be reused between calls.
+    #[inline]
+    pub fn encrypt_into(&mut self, data: &[u8], out: &mut Vec<u8>) {
+        out.clear();
+        out.extend_from_slice(data);
+        self.cipher.encrypt(out)
+    }
 }
 
 /// Return a string of hex characters of a [`Sha1`] hash.
@@ -78,7 +221,7 @@ impl DefaultStreamCipher {
 ///
 /// [hexdigest implementation]: https://wiki.vg/Protocol_Encryption#Authentication
 pub fn hexdigest(hasher: Sha1) -> String {
-    let mut hash = hasher.finish();
+    let mut hash = hasher.finalize();
 
     let negative = (hash[0] & 0x80) == 0x80;
 
@@ -101,26 +244,45 @@ pub fn hexdigest(hasher: Sha1) -> String {
         .join("");
 
     if negative {
-        "-".to_owned() + hash_str.trim_matches('0')
+        "-".to_owned() + hash_str.trim_start_matches('0')
     } else {
-        hash_str.trim_matches('0').to_owned()
+        hash_str.trim_start_matches('0').to_owned()
     }
 }
 
+/// Compute the server hash sent to the session server when joining a server.
+///
+/// This is the [`hexdigest`] of the server id, shared secret and the server's
+/// DER encoded public key, in that order.
+pub fn server_hash(server_id: &str, shared_key: &[u8], public_key: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(server_id.as_bytes());
+    hasher.update(shared_key);
+    hasher.update(public_key);
+    hexdigest(hasher)
+}
+
 /// Encrypted wrapper around a [`TcpStream`].
 pub struct EncryptedTcpStream {
     /// TcpStream to read from.
     stream: TcpStream,
     /// Cipher algorithm.
     cipher: Option<DefaultStreamCipher>,
+    /// Scratch buffer for encrypted writes.
+    buffer: Vec<u8>,
 }
 
 impl EncryptedTcpStream {
     /// Create a new TCP connection to the `address`.
     #[inline]
     pub fn connect(address: &str, cipher: Option<&[u8]>) -> TetsuResult<Self> {
-        let stream = TcpStream::connect(address).unwrap();
-        stream.set_nodelay(true).unwrap();
+        Self::from_stream(TcpStream::connect(address)?, cipher)
+    }
+
+    /// Wrap an already connected stream, such as one tunneled through a proxy.
+    #[inline]
+    pub fn from_stream(stream: TcpStream, cipher: Option<&[u8]>) -> TetsuResult<Self> {
+        stream.set_nodelay(true)?;
 
         Ok(Self {
             stream,
@@ -128,6 +290,7 @@ impl EncryptedTcpStream {
                 Some(key) => Some(DefaultStreamCipher::new(key)?),
                 _ => None,
             },
+            buffer: Vec::new(),
         })
     }
 
@@ -140,8 +303,8 @@ impl EncryptedTcpStream {
 
     /// Get the current connected address.
     #[inline]
-    pub fn get_address(&self) -> SocketAddr {
-        self.stream.peer_addr().unwrap()
+    pub fn get_address(&self) -> TetsuResult<SocketAddr> {
+        Ok(self.stream.peer_addr()?)
     }
 
     /// Enable or disable `TCP_NODELAY`.
@@ -155,6 +318,18 @@ impl EncryptedTcpStream {
     pub fn get_nodelay(&self) -> TetsuResult<bool> {
         Ok(self.stream.nodelay()?)
     }
+
+    /// Set the read timeout of the stream. Reads block indefinitely if `dur` is `None`.
+    #[inline]
+    pub fn set_read_timeout(&self, dur: Option<Duration>) -> TetsuResult<()> {
+        Ok(self.stream.set_read_timeout(dur)?)
+    }
+
+    /// Move the stream into or out of non-blocking mode.
+    #[inline]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> TetsuResult<()> {
+        Ok(self.stream.set_nonblocking(nonblocking)?)
+    }
 }
 
 impl io::Read for EncryptedTcpStream {
@@ -177,10 +352,9 @@ impl io::Write for EncryptedTcpStream {
         match &mut self.cipher {
             None => self.stream.write(buf),
             Some(cipher) => {
-                let mut data = buf.to_owned();
-                cipher.encrypt(&mut data);
-                self.stream.write_all(&data).unwrap();
-                Ok(data.len())
+                cipher.encrypt_into(buf, &mut self.buffer);
+                self.stream.write_all(&self.buffer)?;
+                Ok(buf.len())
             }
         }
     }
@@ -190,3 +364,125 @@ impl io::Write for EncryptedTcpStream {
         self.stream.flush()
     }
 }
+
+/// Encrypted wrapper around an async stream, such as a [`tokio::net::TcpStream`].
+#[cfg(feature = "tokio")]
+pub struct AsyncEncryptedStream<S> {
+    /// Stream to read from.
+    stream: S,
+    /// Cipher algorithm.
+    cipher: Option<DefaultStreamCipher>,
+    /// Encrypted bytes not yet written to the stream.
+    pending: Vec<u8>,
+}
+
+#[cfg(feature = "tokio")]
+impl<S> AsyncEncryptedStream<S> {
+    /// Wrap a stream. Data is sent in plain text until a cipher is set.
+    #[inline]
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            cipher: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Set the key to encrypt with.
+    #[inline]
+    pub fn set_cipher(&mut self, key: &[u8]) -> TetsuResult<()> {
+        self.cipher = Some(DefaultStreamCipher::new(key)?);
+        Ok(())
+    }
+
+    /// Get a reference to the internal stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<S: AsyncWrite + Unpin> AsyncEncryptedStream<S> {
+    /// Write out any buffered encrypted bytes.
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.pending.is_empty() {
+            match Pin::new(&mut self.stream).poll_write(cx, &self.pending)? {
+                Poll::Ready(0) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                Poll::Ready(n) => {
+                    self.pending.drain(..n);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<S: AsyncRead + Unpin> AsyncRead for AsyncEncryptedStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let start = buf.filled().len();
+
+        let poll = Pin::new(&mut this.stream).poll_read(cx, buf);
+
+        if let (Poll::Ready(Ok(())), Some(cipher)) = (&poll, &mut this.cipher) {
+            cipher.decrypt(&mut buf.filled_mut()[start..]);
+        }
+
+        poll
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<S: AsyncWrite + Unpin> AsyncWrite for AsyncEncryptedStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if this.cipher.is_none() {
+            return Pin::new(&mut this.stream).poll_write(cx, buf);
+        }
+
+        // The cipher is stateful, so bytes are encrypted exactly once and
+        // buffered until the stream accepts them.
+        if this.poll_pending(cx)?.is_pending() {
+            return Poll::Pending;
+        }
+
+        if let Some(cipher) = &mut this.cipher {
+            cipher.encrypt_into(buf, &mut this.pending);
+        }
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if this.poll_pending(cx)?.is_pending() {
+            return Poll::Pending;
+        }
+
+        Pin::new(&mut this.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if this.poll_pending(cx)?.is_pending() {
+            return Poll::Pending;
+        }
+
+        Pin::new(&mut this.stream).poll_shutdown(cx)
+    }
+}
@@ -2,10 +2,11 @@
 
 use std::io;
 use std::string;
-use std::sync::{MutexGuard, PoisonError};
+use std::sync::PoisonError;
 
 use cfb8::cipher::errors::InvalidLength;
 use nbt::Error as nbt_error;
+#[cfg(feature = "openssl")]
 use openssl::error::ErrorStack;
 use serde_json::Error as serde_error;
 
@@ -24,15 +25,58 @@ impl std::fmt::Display for InvalidValue {
     }
 }
 
+/// General error type.
 #[derive(Debug)]
 pub enum Error {
     Io(io::Error),
     FromUtf8Error(string::FromUtf8Error),
     Serde(serde_error),
     Nbt(nbt_error),
+    #[cfg(feature = "openssl")]
     SSLErrorStack(ErrorStack),
+    #[cfg(feature = "rustcrypto")]
+    Rsa(rsa::Error),
     InvalidKeyLen(InvalidLength),
     InvalidValue(InvalidValue),
+    Auth(String),
+    /// A connection's lock was poisoned by a panic while it was held.
+    Lock(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "IO error: {}", e),
+            Self::FromUtf8Error(e) => write!(f, "Invalid UTF-8: {}", e),
+            Self::Serde(e) => write!(f, "JSON error: {}", e),
+            Self::Nbt(e) => write!(f, "NBT error: {}", e),
+            #[cfg(feature = "openssl")]
+            Self::SSLErrorStack(e) => write!(f, "OpenSSL error: {}", e),
+            #[cfg(feature = "rustcrypto")]
+            Self::Rsa(e) => write!(f, "RSA error: {}", e),
+            Self::InvalidKeyLen(e) => write!(f, "Invalid key length: {}", e),
+            Self::InvalidValue(e) => e.fmt(f),
+            Self::Auth(e) => write!(f, "Authentication failed: {}", e),
+            Self::Lock(e) => write!(f, "Connection lock error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::FromUtf8Error(e) => Some(e),
+            Self::Serde(e) => Some(e),
+            Self::Nbt(e) => Some(e),
+            #[cfg(feature = "openssl")]
+            Self::SSLErrorStack(e) => Some(e),
+            #[cfg(feature = "rustcrypto")]
+            Self::Rsa(e) => Some(e),
+            Self::InvalidValue(e) => Some(e),
+            Self::InvalidKeyLen(_) | Self::Auth(_) | Self::Lock(_) => None,
+        }
+    }
 }
 
 impl From<io::Error> for Error {
@@ -59,12 +103,20 @@ impl From<nbt_error> for Error {
     }
 }
 
+#[cfg(feature = "openssl")]
 impl From<ErrorStack> for Error {
     fn from(item: ErrorStack) -> Self {
         Self::SSLErrorStack(item)
     }
 }
 
+#[cfg(feature = "rustcrypto")]
+impl From<rsa::Error> for Error {
+    fn from(item: rsa::Error) -> Self {
+        Self::Rsa(item)
+    }
+}
+
 impl From<InvalidLength> for Error {
     fn from(item: InvalidLength) -> Self {
         Self::InvalidKeyLen(item)
@@ -77,28 +129,10 @@ impl From<InvalidValue> for Error {
     }
 }
 
-/// Error while reading/writing from a connection.
-#[derive(Debug)]
-pub enum ConnectionError<'a, T> {
-    LockError(PoisonError<MutexGuard<'a, T>>),
-    Error(Error),
-}
-
-impl<'a, T> From<PoisonError<MutexGuard<'a, T>>> for ConnectionError<'a, T> {
-    fn from(item: PoisonError<MutexGuard<'a, T>>) -> Self {
-        Self::LockError(item)
-    }
-}
-
-impl<'a, T> From<Error> for ConnectionError<'a, T> {
-    fn from(item: Error) -> Self {
-        Self::Error(item)
-    }
-}
-
-impl<'a, T> From<ErrorStack> for ConnectionError<'a, T> {
-    fn from(item: ErrorStack) -> Self {
-        Self::Error(Error::from(item))
+// The guard borrows the connection, so only the message is kept.
+impl<T> From<PoisonError<T>> for Error {
+    fn from(item: PoisonError<T>) -> Self {
+        Self::Lock(item.to_string())
     }
 }
 
@@ -2,10 +2,22 @@
 
 use crate::event::*;
 
+/// Protocol specific event read function.
+pub type ReadCallback<R> =
+    Box<dyn Fn(&mut R, &EventState, &EventDirection, i32) -> TetsuResult<Event>>;
+
+/// Protocol specific event write function. The last argument is the zlib compression level.
+pub type WriteCallback<W> =
+    Box<dyn Fn(&mut W, Event, &EventState, &EventDirection, i32, u32) -> TetsuResult<()>>;
+
+/// Default zlib compression level of written packets.
+pub const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+
 /// Wrapper around protocol specific event read/write impls.
 pub struct EventDispatcher<R: std::io::Read, W: std::io::Write> {
-    reader: Box<dyn Fn(&mut R, &EventState, &EventDirection, i32) -> TetsuResult<Event>>,
-    writer: Box<dyn Fn(&mut W, Event, &EventState, &EventDirection, i32) -> TetsuResult<()>>,
+    reader: ReadCallback<R>,
+    writer: WriteCallback<W>,
+    compression_level: u32,
 }
 
 unsafe impl<R: std::io::Read, W: std::io::Write> Send for EventDispatcher<R, W> {}
@@ -15,25 +27,53 @@ impl<R: std::io::Read, W: std::io::Write> EventDispatcher<R, W> {
     /// Create a new event dispatcher using protocol `version`.
     #[inline]
     pub fn new(version: &ProtocolVersion) -> Self {
-        match version {
-            ProtocolVersion::V47 => Self {
-                reader: versions::v47::get_read_callback(),
-                writer: versions::v47::get_write_callback(),
-            },
-            ProtocolVersion::V754 => Self {
-                reader: versions::v754::get_read_callback(),
-                writer: versions::v754::get_write_callback(),
-            },
+        let (reader, writer) = match version {
+            ProtocolVersion::V47 => (
+                versions::v47::get_read_callback(),
+                versions::v47::get_write_callback(),
+            ),
+            ProtocolVersion::V340 => (
+                versions::v340::get_read_callback(),
+                versions::v340::get_write_callback(),
+            ),
+            ProtocolVersion::V578 => (
+                versions::v578::get_read_callback(),
+                versions::v578::get_write_callback(),
+            ),
+            ProtocolVersion::V754 => (
+                versions::v754::get_read_callback(),
+                versions::v754::get_write_callback(),
+            ),
+        };
+
+        Self {
+            reader,
+            writer,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
         }
     }
 
     /// Create a new [`EventDispatcher`] from any read/write functions.
     #[inline]
-    pub fn new_from_raw(
-        reader: Box<dyn Fn(&mut R, &EventState, &EventDirection, i32) -> TetsuResult<Event>>,
-        writer: Box<dyn Fn(&mut W, Event, &EventState, &EventDirection, i32) -> TetsuResult<()>>,
-    ) -> TetsuResult<Self> {
-        Ok(Self { reader, writer })
+    pub fn new_from_raw(reader: ReadCallback<R>, writer: WriteCallback<W>) -> TetsuResult<Self> {
+        Ok(Self {
+            reader,
+            writer,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+        })
+    }
+
+    /// Set the zlib compression level of written packets, from 0 (none) to 9 (best).
+    /// Defaults to [`DEFAULT_COMPRESSION_LEVEL`].
+    #[inline]
+    pub fn set_compression_level(&mut self, level: u32) {
+        self.compression_level = level.min(9);
+    }
+
+    /// Get the zlib compression level of written packets.
+    #[inline]
+    pub fn get_compression_level(&self) -> u32 {
+        self.compression_level
     }
 
     /// Read an event from the buffer.
@@ -58,6 +98,13 @@ impl<R: std::io::Read, W: std::io::Write> EventDispatcher<R, W> {
         direction: &EventDirection,
         compression_threshold: i32,
     ) -> TetsuResult<()> {
-        (self.writer)(buf, event, state, direction, compression_threshold)
+        (self.writer)(
+            buf,
+            event,
+            state,
+            direction,
+            compression_threshold,
+            self.compression_level,
+        )
     }
 }
@@ -15,6 +15,7 @@ let dispatcher: dispatcher::EventDispatcher<Cursor<Vec<u8>>, Cursor<Vec<u8>>> =
 // ...
 
 let handshake = Event::Handshake(Handshake {
+    protocol_version: None,
     server_address: "127.0.0.1".to_owned(),
     server_port: 25565,
     next_state: EventState::Login,
@@ -31,8 +32,11 @@ dispatcher.write_event(
 */
 
 use crate::errors::*;
+use crate::serialization::{Readable, Writable};
 use crate::versions;
 
+use std::convert::TryFrom;
+
 use uuid::Uuid;
 
 pub mod dispatcher;
@@ -92,12 +96,15 @@ pub enum Event {
     // Client bound ----------------------------------
     KeepAlive(KeepAlive),
     JoinGame(JoinGame),
+    ChatMessage(ChatMessage),
     TimeUpdate(TimeUpdate),
     SpawnPosition(SpawnPosition),
     PlayerPositionAndLook(PlayerPositionAndLook),
     HeldItemChange(HeldItemChange),
+    OpenWindow(OpenWindow),
     SlotUpdate(SlotUpdate),
     WindowItemsUpdate(WindowItemsUpdate),
+    WindowProperty(WindowProperty),
     Statistics(Statistics),
     PlayerInfoUpdate(PlayerInfoUpdate),
     PlayerAbility(PlayerAbility),
@@ -105,9 +112,328 @@ pub enum Event {
     ServerDifficultyUpdate(ServerDifficultyUpdate),
     WorldBorder(WorldBorder),
     ChangeGameState(ChangeGameState),
+    SpawnPlayer(SpawnPlayer),
+    SpawnMob(SpawnMob),
+    DestroyEntities(DestroyEntities),
+    EntityRelativeMove(EntityRelativeMove),
+    EntityLookAndRelativeMove(EntityLookAndRelativeMove),
+    EntityTeleport(EntityTeleport),
+    EntityVelocity(EntityVelocity),
+    EntityHeadLook(EntityHeadLook),
+    EntityAnimation(EntityAnimation),
+    EntityEquipment(EntityEquipment),
+    Camera(Camera),
+    CollectItem(CollectItem),
+    EntityStatus(EntityStatus),
+    AttachEntity(AttachEntity),
+    Explosion(Explosion),
+    MultiBlockChange(MultiBlockChange),
+    BlockChange(BlockChange),
+    ChunkData(ChunkData),
+    MapChunkBulk(MapChunkBulk),
+    TabCompleteResponse(TabCompleteResponse),
+    UpdateHealth(UpdateHealth),
+    SetExperience(SetExperience),
+    NamedSoundEffect(NamedSoundEffect),
+    Title(Title),
+    BossBar(BossBar),
+    Respawn(Respawn),
 
     // Server bound ----------------------------------
     KeepAliveResponse(KeepAliveResponse),
+    SendChatMessage(SendChatMessage),
+    PlayerDigging(PlayerDigging),
+    PlayerBlockPlacement(PlayerBlockPlacement),
+    TabComplete(TabComplete),
+    ClientStatus(ClientStatus),
+    PlayerPosition(PlayerPosition),
+    PlayerLook(PlayerLook),
+    PlayerPositionAndLookServerbound(PlayerPositionAndLookServerbound),
+    TeleportConfirm(TeleportConfirm),
+    ClickWindow(ClickWindow),
+    CloseWindow(CloseWindow),
+    ConfirmTransaction(ConfirmTransaction),
+    ClientSettings(ClientSettings),
+    Animation(Animation),
+    InteractEntity(InteractEntity),
+    SetHeldItem(SetHeldItem),
+    Spectate(Spectate),
+
+    /// Packet with an id that isn't implemented for the protocol version.
+    Unknown {
+        id: i32,
+        state: EventState,
+        direction: EventDirection,
+        /// Raw packet contents following the id.
+        data: Vec<u8>,
+    },
+}
+
+impl Event {
+    /// Get the state this event is normally sent in.
+    ///
+    /// Events that aren't tied to a single state, such as a play [`Disconnect`],
+    /// return the state of the packet they are usually read from.
+    pub fn default_state(&self) -> EventState {
+        match self {
+            Event::Handshake(_) => EventState::Handshake,
+
+            Event::Pong(_)
+            | Event::StatusResponse(_)
+            | Event::Ping(_)
+            | Event::StatusRequest(_) => EventState::Status,
+
+            Event::Disconnect(_)
+            | Event::EncryptionRequest(_)
+            | Event::LoginSuccess(_)
+            | Event::SetCompression(_)
+            | Event::LoginStart(_)
+            | Event::EncryptionResponse(_) => EventState::Login,
+
+            Event::KeepAlive(_)
+            | Event::JoinGame(_)
+            | Event::ChatMessage(_)
+            | Event::TimeUpdate(_)
+            | Event::SpawnPosition(_)
+            | Event::PlayerPositionAndLook(_)
+            | Event::HeldItemChange(_)
+            | Event::OpenWindow(_)
+            | Event::SlotUpdate(_)
+            | Event::WindowItemsUpdate(_)
+            | Event::WindowProperty(_)
+            | Event::Statistics(_)
+            | Event::PlayerInfoUpdate(_)
+            | Event::PlayerAbility(_)
+            | Event::PluginMessage(_)
+            | Event::ServerDifficultyUpdate(_)
+            | Event::WorldBorder(_)
+            | Event::ChangeGameState(_)
+            | Event::SpawnPlayer(_)
+            | Event::SpawnMob(_)
+            | Event::DestroyEntities(_)
+            | Event::EntityRelativeMove(_)
+            | Event::EntityLookAndRelativeMove(_)
+            | Event::EntityTeleport(_)
+            | Event::EntityVelocity(_)
+            | Event::EntityHeadLook(_)
+            | Event::EntityAnimation(_)
+            | Event::EntityEquipment(_)
+            | Event::Camera(_)
+            | Event::CollectItem(_)
+            | Event::EntityStatus(_)
+            | Event::AttachEntity(_)
+            | Event::Explosion(_)
+            | Event::MultiBlockChange(_)
+            | Event::BlockChange(_)
+            | Event::ChunkData(_)
+            | Event::MapChunkBulk(_)
+            | Event::KeepAliveResponse(_)
+            | Event::SendChatMessage(_)
+            | Event::PlayerDigging(_)
+            | Event::PlayerBlockPlacement(_)
+            | Event::TabComplete(_)
+            | Event::TabCompleteResponse(_)
+            | Event::UpdateHealth(_)
+            | Event::SetExperience(_)
+            | Event::NamedSoundEffect(_)
+            | Event::Title(_)
+            | Event::BossBar(_)
+            | Event::ClientStatus(_)
+            | Event::Respawn(_)
+            | Event::PlayerPosition(_)
+            | Event::PlayerLook(_)
+            | Event::PlayerPositionAndLookServerbound(_)
+            | Event::TeleportConfirm(_)
+            | Event::ClickWindow(_)
+            | Event::CloseWindow(_)
+            | Event::ConfirmTransaction(_)
+            | Event::ClientSettings(_)
+            | Event::Animation(_)
+            | Event::InteractEntity(_)
+            | Event::SetHeldItem(_)
+            | Event::Spectate(_) => EventState::Play,
+
+            Event::Unknown { state, .. } => *state,
+        }
+    }
+
+    /// Get the direction this event is normally sent in.
+    pub fn default_direction(&self) -> EventDirection {
+        match self {
+            Event::Handshake(_)
+            | Event::Ping(_)
+            | Event::StatusRequest(_)
+            | Event::LoginStart(_)
+            | Event::EncryptionResponse(_)
+            | Event::KeepAliveResponse(_)
+            | Event::SendChatMessage(_)
+            | Event::PlayerDigging(_)
+            | Event::PlayerBlockPlacement(_)
+            | Event::TabComplete(_)
+            | Event::ClientStatus(_)
+            | Event::PlayerPosition(_)
+            | Event::PlayerLook(_)
+            | Event::PlayerPositionAndLookServerbound(_)
+            | Event::TeleportConfirm(_)
+            | Event::ClickWindow(_)
+            | Event::CloseWindow(_)
+            | Event::ConfirmTransaction(_)
+            | Event::ClientSettings(_)
+            | Event::Animation(_)
+            | Event::InteractEntity(_)
+            | Event::SetHeldItem(_)
+            | Event::Spectate(_) => EventDirection::ServerBound,
+
+            Event::Pong(_)
+            | Event::StatusResponse(_)
+            | Event::Disconnect(_)
+            | Event::EncryptionRequest(_)
+            | Event::LoginSuccess(_)
+            | Event::SetCompression(_)
+            | Event::KeepAlive(_)
+            | Event::JoinGame(_)
+            | Event::ChatMessage(_)
+            | Event::TimeUpdate(_)
+            | Event::SpawnPosition(_)
+            | Event::PlayerPositionAndLook(_)
+            | Event::HeldItemChange(_)
+            | Event::OpenWindow(_)
+            | Event::SlotUpdate(_)
+            | Event::WindowItemsUpdate(_)
+            | Event::WindowProperty(_)
+            | Event::Statistics(_)
+            | Event::PlayerInfoUpdate(_)
+            | Event::PlayerAbility(_)
+            | Event::PluginMessage(_)
+            | Event::ServerDifficultyUpdate(_)
+            | Event::WorldBorder(_)
+            | Event::ChangeGameState(_)
+            | Event::SpawnPlayer(_)
+            | Event::SpawnMob(_)
+            | Event::DestroyEntities(_)
+            | Event::EntityRelativeMove(_)
+            | Event::EntityLookAndRelativeMove(_)
+            | Event::EntityTeleport(_)
+            | Event::EntityVelocity(_)
+            | Event::EntityHeadLook(_)
+            | Event::EntityAnimation(_)
+            | Event::EntityEquipment(_)
+            | Event::Camera(_)
+            | Event::CollectItem(_)
+            | Event::EntityStatus(_)
+            | Event::AttachEntity(_)
+            | Event::Explosion(_)
+            | Event::MultiBlockChange(_)
+            | Event::BlockChange(_)
+            | Event::ChunkData(_)
+            | Event::MapChunkBulk(_)
+            | Event::TabCompleteResponse(_)
+            | Event::UpdateHealth(_)
+            | Event::SetExperience(_)
+            | Event::NamedSoundEffect(_)
+            | Event::Title(_)
+            | Event::BossBar(_)
+            | Event::Respawn(_) => EventDirection::ClientBound,
+
+            Event::Unknown { direction, .. } => *direction,
+        }
+    }
+}
+
+/// Implement `TryFrom<Event>` for the struct of each event variant, returning
+/// the event back if it is a different variant.
+macro_rules! event_try_from_impl {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl TryFrom<Event> for $name {
+                type Error = Event;
+
+                #[inline]
+                fn try_from(event: Event) -> Result<Self, Self::Error> {
+                    match event {
+                        Event::$name(e) => Ok(e),
+                        e => Err(e),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+event_try_from_impl! {
+    Handshake,
+    Pong,
+    StatusResponse,
+    Ping,
+    StatusRequest,
+    Disconnect,
+    EncryptionRequest,
+    LoginSuccess,
+    SetCompression,
+    LoginStart,
+    EncryptionResponse,
+    KeepAlive,
+    JoinGame,
+    ChatMessage,
+    TimeUpdate,
+    SpawnPosition,
+    PlayerPositionAndLook,
+    HeldItemChange,
+    OpenWindow,
+    SlotUpdate,
+    WindowItemsUpdate,
+    WindowProperty,
+    Statistics,
+    PlayerInfoUpdate,
+    PlayerAbility,
+    PluginMessage,
+    ServerDifficultyUpdate,
+    WorldBorder,
+    ChangeGameState,
+    SpawnPlayer,
+    SpawnMob,
+    DestroyEntities,
+    EntityRelativeMove,
+    EntityLookAndRelativeMove,
+    EntityTeleport,
+    EntityVelocity,
+    EntityHeadLook,
+    EntityAnimation,
+    EntityEquipment,
+    Camera,
+    CollectItem,
+    EntityStatus,
+    AttachEntity,
+    Explosion,
+    MultiBlockChange,
+    BlockChange,
+    ChunkData,
+    MapChunkBulk,
+    TabCompleteResponse,
+    UpdateHealth,
+    SetExperience,
+    NamedSoundEffect,
+    Title,
+    BossBar,
+    Respawn,
+    KeepAliveResponse,
+    SendChatMessage,
+    PlayerDigging,
+    PlayerBlockPlacement,
+    TabComplete,
+    ClientStatus,
+    PlayerPosition,
+    PlayerLook,
+    PlayerPositionAndLookServerbound,
+    TeleportConfirm,
+    ClickWindow,
+    CloseWindow,
+    ConfirmTransaction,
+    ClientSettings,
+    Animation,
+    InteractEntity,
+    SetHeldItem,
+    Spectate,
 }
 
 unsafe impl Send for Event {}
@@ -145,6 +471,9 @@ pub struct StatusResponse {
 /// Handshake packet. This begins the server connection.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Handshake {
+    /// Protocol version of the client. Written as the dispatcher's
+    /// version if `None`.
+    pub protocol_version: Option<i32>,
     /// Server IP string.
     pub server_address: String,
     /// Server port.
@@ -224,6 +553,177 @@ pub struct KeepAliveResponse {
     pub id: i64,
 }
 
+/// Chat message or command sent by the client.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SendChatMessage {
+    /// Message contents. At most 256 characters.
+    pub message: String,
+}
+
+/// Sent when the player starts or stops digging, or drops items.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlayerDigging {
+    pub status: DiggingStatus,
+    pub location: Position,
+    pub face: BlockFace,
+}
+
+/// Place a block or use the held item.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlayerBlockPlacement {
+    pub location: Position,
+    pub face: BlockFace,
+    pub held_item: Slot,
+    /// Position of the crosshair on the block, in sixteenths of a block.
+    pub cursor_x: i8,
+    pub cursor_y: i8,
+    pub cursor_z: i8,
+}
+
+/// Request completions for a partially typed chat message or command.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TabComplete {
+    pub text: String,
+    /// Block the player is looking at, if any.
+    pub looked_at: Option<Position>,
+}
+
+/// Completions sent in response to [`TabComplete`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct TabCompleteResponse {
+    pub matches: Vec<String>,
+}
+
+/// Sent when the client is ready to respawn or wants its statistics.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClientStatus {
+    pub action: ClientStatusAction,
+}
+
+/// Update the player's position.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlayerPosition {
+    pub x: f64,
+    /// Y coordinate of the player's feet.
+    pub feet_y: f64,
+    pub z: f64,
+    pub on_ground: bool,
+}
+
+/// Update the player's rotation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlayerLook {
+    /// Absolute rotation in degrees.
+    pub yaw: f32,
+    pub pitch: f32,
+    pub on_ground: bool,
+}
+
+/// Update the player's position and rotation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlayerPositionAndLookServerbound {
+    pub x: f64,
+    /// Y coordinate of the player's feet.
+    pub feet_y: f64,
+    pub z: f64,
+    /// Absolute rotation in degrees.
+    pub yaw: f32,
+    pub pitch: f32,
+    pub on_ground: bool,
+}
+
+/// Confirm a [`PlayerPositionAndLook`] sent by the server.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TeleportConfirm {
+    pub teleport_id: i32,
+}
+
+/// Sent when the player clicks a slot in a window.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClickWindow {
+    pub window_id: u8,
+    pub slot: i16,
+    pub button: i8,
+    /// Unique number for the click, used by the server to confirm it.
+    pub action_number: i16,
+    /// Kind of click, which together with `button` decides the action.
+    pub mode: i32,
+    /// Item in the clicked slot.
+    pub clicked_item: Slot,
+}
+
+/// Sent when a window is closed. Sent by both the client and the server.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CloseWindow {
+    pub window_id: u8,
+}
+
+/// Sent by the server to accept or reject a window click, and by the client to
+/// apologize for a rejected one. Sent by both the client and the server.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfirmTransaction {
+    pub window_id: u8,
+    /// Action number of the click being confirmed.
+    pub action_number: i16,
+    pub accepted: bool,
+}
+
+/// Sent by the client to tell the server its settings.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClientSettings {
+    /// Locale, such as `en_US`.
+    pub locale: String,
+    /// Render distance in chunks.
+    pub view_distance: i8,
+    /// 0 for full chat, 1 for commands only and 2 for hidden.
+    pub chat_mode: i32,
+    pub chat_colors: bool,
+    /// Bit mask of the skin parts to show, with the highest bit unused.
+    pub displayed_skin_parts: u8,
+    /// 0 for left and 1 for right. Not sent in 1.8, where it is always right.
+    pub main_hand: i32,
+}
+
+/// Sent when the player swings their arm.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Animation {
+    /// Hand that is swung. Always the main hand in 1.8.
+    pub hand: Hand,
+}
+
+/// Sent when the player attacks or right clicks an entity.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InteractEntity {
+    pub entity_id: i32,
+    pub interaction: EntityInteraction,
+}
+
+/// Sent when the player changes their selected hotbar slot.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetHeldItem {
+    /// Hotbar slot, from 0 to 8.
+    pub slot: i16,
+}
+
+/// Sent by a spectator to teleport to an entity.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Spectate {
+    pub target: Uuid,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            locale: "en_US".to_owned(),
+            view_distance: 8,
+            chat_mode: 0,
+            chat_colors: true,
+            displayed_skin_parts: 0x7F,
+            main_hand: 1,
+        }
+    }
+}
+
 /// Sent when a player joins a server.
 #[derive(Debug, PartialEq, Clone)]
 pub struct JoinGame {
@@ -246,12 +746,50 @@ pub struct JoinGame {
     pub is_flat: Option<bool>,
 }
 
+/// Chat message sent by the server.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChatMessage {
+    /// Message contents.
+    pub message: Chat,
+    /// Where the message is displayed.
+    pub position: ChatPosition,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct TimeUpdate {
     pub world_age: i64,
+    /// Negative if the time of day is locked.
     pub time_of_day: i64,
 }
 
+impl TimeUpdate {
+    /// Number of ticks in a Minecraft day.
+    pub const TICKS_PER_DAY: i64 = 24000;
+
+    /// Get the number of ticks into the current day, from `0` to `23999`.
+    /// Locked time is handled by ignoring the sign.
+    pub fn time_of_day_ticks(&self) -> i64 {
+        (self.time_of_day.unsigned_abs() % Self::TICKS_PER_DAY as u64) as i64
+    }
+
+    /// Check if the time of day is locked, such as when the `doDaylightCycle`
+    /// gamerule is false.
+    #[inline]
+    pub fn is_time_locked(&self) -> bool {
+        self.time_of_day < 0
+    }
+
+    /// Get the part of the day.
+    pub fn day_phase(&self) -> DayPhase {
+        match self.time_of_day_ticks() {
+            0..=11999 => DayPhase::Day,
+            12000..=12999 => DayPhase::Sunset,
+            13000..=22999 => DayPhase::Night,
+            _ => DayPhase::Sunrise,
+        }
+    }
+}
+
 /// Spawn position of a player.
 #[derive(Debug, PartialEq, Clone)]
 pub struct SpawnPosition {
@@ -266,12 +804,25 @@ pub struct HeldItemChange {
     pub slot: i8,
 }
 
+/// Open a window on the client.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OpenWindow {
+    pub window_id: u8,
+    /// Window type identifier, such as `minecraft:chest`.
+    pub window_type: String,
+    pub title: Chat,
+    /// Number of slots, not counting the player's inventory.
+    pub slot_count: u8,
+    /// Horse the window belongs to. Only present for `EntityHorse` windows.
+    pub entity_id: Option<i32>,
+}
+
 /// Update a single window slot.
 #[derive(Debug, PartialEq, Clone)]
 pub struct SlotUpdate {
     pub window_id: i8,
     pub slot: i16,
-    pub data: Slot
+    pub data: Slot,
 }
 
 /// Update player's window slots.
@@ -281,6 +832,15 @@ pub struct WindowItemsUpdate {
     pub slots: Vec<Slot>,
 }
 
+/// Update a property of a window, such as furnace progress or enchantment levels.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WindowProperty {
+    pub window_id: u8,
+    /// Property to update. Its meaning depends on the window type.
+    pub property: i16,
+    pub value: i16,
+}
+
 /// A single stat value.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Statistic {
@@ -288,12 +848,37 @@ pub struct Statistic {
     pub value: i32,
 }
 
+impl Statistic {
+    /// Classify the stat by the prefix of its identifier.
+    pub fn category(&self) -> StatCategory {
+        let categories = [
+            ("stat.mineBlock.", StatCategory::Mined),
+            ("stat.craftItem.", StatCategory::Crafted),
+            ("stat.useItem.", StatCategory::Used),
+            ("stat.breakItem.", StatCategory::Broken),
+            ("stat.", StatCategory::General),
+        ];
+
+        categories
+            .iter()
+            .find(|(prefix, _)| self.name.starts_with(prefix))
+            .map_or(StatCategory::Custom, |(_, category)| *category)
+    }
+}
+
 /// Player statistics.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Statistics {
     pub values: Vec<Statistic>,
 }
 
+impl Statistics {
+    /// Get the value of a stat by its identifier.
+    pub fn get(&self, name: &str) -> Option<i32> {
+        self.values.iter().find(|s| s.name == name).map(|s| s.value)
+    }
+}
+
 /// Sent to update the player list on a client.
 #[derive(Debug, PartialEq, Clone)]
 pub struct PlayerInfoUpdate {
@@ -312,8 +897,6 @@ pub struct PlayerAbility {
     pub walking_speed: f32,
 }
 
-// TODO: serialize data as `enum` based on namespace.
-
 /// Plugin channel message.
 #[derive(Debug, PartialEq, Clone)]
 pub struct PluginMessage {
@@ -323,6 +906,44 @@ pub struct PluginMessage {
     pub data: Vec<u8>,
 }
 
+impl PluginMessage {
+    /// Constructs a message announcing a client or server brand. Versions
+    /// before 1.13 use the legacy `MC|Brand` channel.
+    pub fn brand(name: &str, version: &ProtocolVersion) -> Self {
+        let mut data = Vec::new();
+        // Writing to a `Vec` can't fail.
+        name.to_owned().write_to(&mut data).unwrap();
+
+        let channel = match version {
+            ProtocolVersion::V47 | ProtocolVersion::V340 => "MC|Brand",
+            _ => "minecraft:brand",
+        };
+
+        Self {
+            channel: channel.to_owned(),
+            data,
+        }
+    }
+
+    /// Decode the message data for channels that are known.
+    pub fn parse(&self) -> KnownPluginMessage {
+        match &self.channel[..] {
+            "minecraft:brand" | "MC|Brand" => {
+                let mut data = &self.data[..];
+                if let Ok(brand) = String::read_from(&mut data) {
+                    return KnownPluginMessage::Brand(brand);
+                }
+            }
+            _ => {}
+        }
+
+        KnownPluginMessage::Unknown {
+            channel: self.channel.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
 /// Sent when the server changes its difficulty.
 #[derive(Debug, PartialEq, Clone)]
 pub struct ServerDifficultyUpdate {
@@ -399,3 +1020,287 @@ pub enum ChangeGameState {
     FadeTime(f32),
     MobAppear,
 }
+
+/// Sent when another player comes into view.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpawnPlayer {
+    pub entity_id: i32,
+    pub uuid: Uuid,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: Angle,
+    pub pitch: Angle,
+    /// Id of the item in the player's hand, 0 if empty.
+    pub current_item: i16,
+    pub metadata: EntityMetadata,
+}
+
+/// Sent when a mob comes into view.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpawnMob {
+    pub entity_id: i32,
+    /// Not sent before 1.9.
+    pub uuid: Option<Uuid>,
+    pub entity_type: i32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: Angle,
+    pub pitch: Angle,
+    pub head_pitch: Angle,
+    /// Velocity in units of 1/8000 of a block per tick.
+    pub velocity: (i16, i16, i16),
+    pub metadata: EntityMetadata,
+}
+
+/// Sent when entities are removed from the client.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DestroyEntities {
+    pub entity_ids: Vec<i32>,
+}
+
+/// Sent when an entity moves less than 4 blocks.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EntityRelativeMove {
+    pub entity_id: i32,
+    /// Change in position, in blocks.
+    pub delta_x: f64,
+    pub delta_y: f64,
+    pub delta_z: f64,
+    pub on_ground: bool,
+}
+
+/// Sent when an entity rotates and moves less than 4 blocks.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EntityLookAndRelativeMove {
+    pub entity_id: i32,
+    /// Change in position, in blocks.
+    pub delta_x: f64,
+    pub delta_y: f64,
+    pub delta_z: f64,
+    /// New absolute rotation.
+    pub yaw: Angle,
+    pub pitch: Angle,
+    pub on_ground: bool,
+}
+
+/// Sent when an entity moves 4 blocks or more.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EntityTeleport {
+    pub entity_id: i32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: Angle,
+    pub pitch: Angle,
+    pub on_ground: bool,
+}
+
+/// Sent when an entity's velocity changes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EntityVelocity {
+    pub entity_id: i32,
+    /// Velocity in units of 1/8000 of a block per tick.
+    pub velocity_x: i16,
+    pub velocity_y: i16,
+    pub velocity_z: i16,
+}
+
+impl EntityVelocity {
+    /// Get the velocity in blocks per tick.
+    pub fn to_blocks_per_tick(&self) -> (f64, f64, f64) {
+        (
+            self.velocity_x as f64 / 8000.0,
+            self.velocity_y as f64 / 8000.0,
+            self.velocity_z as f64 / 8000.0,
+        )
+    }
+}
+
+/// Sent when an entity turns its head.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EntityHeadLook {
+    pub entity_id: i32,
+    pub head_yaw: Angle,
+}
+
+/// Sent when an entity plays an animation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EntityAnimation {
+    pub entity_id: i32,
+    pub animation: AnimationType,
+}
+
+/// Sent when an entity's held item or armor changes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EntityEquipment {
+    pub entity_id: i32,
+    pub slot: EquipmentSlot,
+    pub item: Slot,
+}
+
+/// Sent to make the player view the world from an entity.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Camera {
+    /// Entity to view from, or the player's own id to reset the camera.
+    pub camera_id: i32,
+}
+
+/// Sent when an entity picks up an item, arrow or experience orb.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CollectItem {
+    pub collected_entity_id: i32,
+    pub collector_entity_id: i32,
+}
+
+/// Sent when an entity's status changes, such as when it is hurt or dies.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EntityStatus {
+    pub entity_id: i32,
+    pub status: u8,
+}
+
+impl EntityStatus {
+    /// Get the meaning of the status code, if it is a known one.
+    pub fn status_kind(&self) -> Option<EntityStatusKind> {
+        Some(match self.status {
+            2 => EntityStatusKind::Hurt,
+            3 => EntityStatusKind::Death,
+            6 => EntityStatusKind::TamingFailed,
+            7 => EntityStatusKind::TamingSucceeded,
+            8 => EntityStatusKind::ShakingWater,
+            9 => EntityStatusKind::EatingFinished,
+            10 => EntityStatusKind::EatingGrass,
+            18 => EntityStatusKind::InLove,
+            22 => EntityStatusKind::EnableReducedDebugInfo,
+            23 => EntityStatusKind::DisableReducedDebugInfo,
+            _ => return None,
+        })
+    }
+}
+
+/// Sent when an entity is leashed to or mounts another entity, or is let go.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AttachEntity {
+    pub attached_entity_id: i32,
+    /// Leash holder or vehicle, or `-1` to detach.
+    pub holding_entity_id: i32,
+    /// Whether the entity is leashed rather than riding.
+    pub leash: bool,
+}
+
+/// Sent when an explosion occurs.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Explosion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub radius: f32,
+    /// Offsets of destroyed blocks, relative to the center of the explosion.
+    pub records: Vec<(i8, i8, i8)>,
+    /// Velocity added to the player by the explosion.
+    pub player_motion: (f32, f32, f32),
+}
+
+/// Sent when several blocks in a chunk change at once.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultiBlockChange {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub records: Vec<BlockChangeRecord>,
+}
+
+/// Sent when a single block changes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BlockChange {
+    pub location: Position,
+    /// Block id and metadata, packed as `id << 4 | meta`.
+    pub block_state: i32,
+}
+
+/// Sent to load or update a chunk column.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChunkData {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    /// Set when the full column is sent, including biomes. Otherwise only
+    /// the present sections are replaced.
+    pub ground_up_continuous: bool,
+    pub chunk: Chunk,
+}
+
+/// Sent to load several full chunk columns at once.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MapChunkBulk {
+    /// Whether sections include sky light, which is only sent in dimensions with a sky.
+    pub sky_light: bool,
+    /// Columns in the order they are sent. These are always ground up continuous.
+    pub chunks: Vec<ChunkData>,
+}
+
+/// Sent when the player's health, food or saturation changes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UpdateHealth {
+    /// Health out of 20. The player is dead at 0.
+    pub health: f32,
+    /// Food out of 20.
+    pub food: i32,
+    pub saturation: f32,
+}
+
+/// Sent when the player's experience changes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetExperience {
+    /// Progress towards the next level, between 0 and 1.
+    pub experience_bar: f32,
+    pub level: i32,
+    pub total_experience: i32,
+}
+
+/// Sent when a sound is played at a position.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NamedSoundEffect {
+    /// Sound name, such as `random.explode`.
+    pub sound: String,
+    /// Position of the sound, in blocks.
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    /// Volume, where 1 is 100%.
+    pub volume: f32,
+    /// Pitch, where 63 is 100%.
+    pub pitch: u8,
+}
+
+/// Sent to show, update or clear the title in the middle of the screen.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Title {
+    pub action: TitleAction,
+}
+
+/// Sent to add, update or remove a boss bar.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BossBar {
+    /// Unique id of the bar.
+    pub uuid: Uuid,
+    pub action: BossBarAction,
+}
+
+/// Sent when the player respawns or changes dimension.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Respawn {
+    pub dimension: Dimension,
+    pub difficulty: Option<Difficulty>,
+    pub gamemode: Gamemode,
+    pub level_type: Option<String>,
+    /// Dimension type the player is spawning into.
+    pub dimension_codec: Option<nbt::Blob>,
+    pub world_name: Option<String>,
+    pub hashed_seed: Option<i64>,
+    pub previous_gamemode: Option<Gamemode>,
+    pub is_debug: Option<bool>,
+    pub is_flat: Option<bool>,
+    /// Keep entity metadata, set when respawning after the end credits.
+    pub copy_metadata: Option<bool>,
+}
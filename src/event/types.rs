@@ -1,9 +1,13 @@
 //! Types used by events.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use serde_repr::*;
 use uuid::Uuid;
 
+use crate::errors::{Error, InvalidValue, TetsuResult};
+
 /// All supported protocol versions.
 #[non_exhaustive]
 #[derive(Serialize_repr, Deserialize_repr, Debug, PartialEq, Eq, Clone, Copy)]
@@ -11,6 +15,10 @@ use uuid::Uuid;
 pub enum ProtocolVersion {
     /// Server versions 1.8-1.8.9
     V47 = 47,
+    /// Server version 1.12.2
+    V340 = 340,
+    /// Server version 1.15.2
+    V578 = 578,
     /// Server versions 1.16.4 and 1.16.5
     V754 = 754,
 }
@@ -67,15 +75,7 @@ pub enum Difficulty {
 #[serde(untagged)]
 pub enum ServerDescription {
     Short(String),
-    Long(ServerDescriptionLong),
-}
-
-/// Long server description.
-///
-/// **Warning:** All fields haven't been covered yet.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
-pub struct ServerDescriptionLong {
-    pub text: String,
+    Long(Chat),
 }
 
 /// General server player information.
@@ -83,6 +83,16 @@ pub struct ServerDescriptionLong {
 pub struct ServerPlayers {
     pub max: u32,
     pub online: u16,
+    /// Some of the players that are online, if the server lists them.
+    pub sample: Option<Vec<PlayerSample>>,
+}
+
+/// A player listed in a server's status.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct PlayerSample {
+    pub name: String,
+    /// The player's UUID as a hyphenated string.
+    pub id: String,
 }
 
 /// Version the server is running on.
@@ -107,6 +117,56 @@ pub struct Position {
     pub z: i64,
 }
 
+/// Rotation angle in steps of 1/256 of a full turn.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Angle(pub u8);
+
+impl Angle {
+    /// Convert an angle in degrees, wrapping it to a single turn.
+    #[inline]
+    pub fn from_degrees(degrees: f32) -> Self {
+        Self((degrees.rem_euclid(360.0) * 256.0 / 360.0).round() as u32 as u8)
+    }
+
+    /// Get the angle in degrees.
+    #[inline]
+    pub fn to_degrees(self) -> f32 {
+        self.0 as f32 * 360.0 / 256.0
+    }
+}
+
+/// Set of bits packed into longs, used by masks in newer protocol versions.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct BitSet(pub Vec<u64>);
+
+impl BitSet {
+    /// Check if a bit is set.
+    #[inline]
+    pub fn get(&self, bit: usize) -> bool {
+        match self.0.get(bit / 64) {
+            Some(l) => l & (1 << (bit % 64)) != 0,
+            _ => false,
+        }
+    }
+
+    /// Set or clear a bit, growing the set if needed.
+    #[inline]
+    pub fn set(&mut self, bit: usize, val: bool) {
+        if bit / 64 >= self.0.len() {
+            if !val {
+                return;
+            }
+            self.0.resize(bit / 64 + 1, 0);
+        }
+
+        if val {
+            self.0[bit / 64] |= 1 << (bit % 64);
+        } else {
+            self.0[bit / 64] &= !(1 << (bit % 64));
+        }
+    }
+}
+
 // ---- Chat ---------------
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
@@ -116,7 +176,7 @@ pub struct Action {
 }
 
 /// Information that defines contents/style of a chat message.
-#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Chat {
     pub text: Option<String>,
@@ -130,23 +190,658 @@ pub struct Chat {
     pub click_event: Option<Action>,
     pub hover_event: Option<Action>,
     pub extra: Option<Vec<Self>>,
+    /// Arguments of a translated component.
+    pub with: Option<Vec<Self>>,
+}
+
+impl Chat {
+    /// Render the message as plain text, dropping all formatting.
+    /// Translated components are rendered as their `{key}`.
+    pub fn to_plain_string(&self) -> String {
+        let mut out = String::new();
+        self.write_plain(&mut out, None);
+        out
+    }
+
+    /// Render the message as plain text, translating components with `lang`.
+    ///
+    /// `%s` and `%1$s` style placeholders in a translation are substituted with
+    /// the rendered `with` arguments. Keys missing from `lang` are rendered as is.
+    pub fn translate_with(&self, lang: &HashMap<String, String>) -> String {
+        let mut out = String::new();
+        self.write_plain(&mut out, Some(lang));
+        out
+    }
+
+    /// Get the style set on this component, with unset flags resolved to `false`.
+    pub fn style(&self) -> ChatStyle {
+        ChatStyle {
+            color: self.color.as_deref().and_then(ChatColor::parse),
+            bold: self.bold.unwrap_or_default(),
+            italic: self.italic.unwrap_or_default(),
+            underlined: self.underlined.unwrap_or_default(),
+            strikethrough: self.strikethrough.unwrap_or_default(),
+            obfuscated: self.obfuscated.unwrap_or_default(),
+        }
+    }
+
+    /// Render the message with ANSI escape codes for terminals.
+    /// Translated components are rendered as their `{key}`.
+    pub fn to_ansi_string(&self) -> String {
+        let mut out = String::new();
+        self.write_ansi(&mut out, &AnsiStyle::default());
+        out.push_str("\x1b[0m");
+        out
+    }
+
+    /// Unformatted content of this component, excluding `extra`.
+    fn content(&self, lang: Option<&HashMap<String, String>>) -> String {
+        match (&self.text, &self.translate, lang) {
+            (Some(t), _, _) => t.clone(),
+            (_, Some(k), Some(lang)) => match lang.get(k) {
+                Some(template) => self.substitute(template, lang),
+                _ => k.clone(),
+            },
+            (_, Some(k), _) => format!("{{{}}}", k),
+            _ => String::new(),
+        }
+    }
+
+    /// Substitute the `with` arguments into a translation template.
+    fn substitute(&self, template: &str, lang: &HashMap<String, String>) -> String {
+        let args = self
+            .with
+            .iter()
+            .flatten()
+            .map(|c| c.translate_with(lang))
+            .collect::<Vec<_>>();
+
+        let mut out = String::new();
+        let mut next = 0;
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+
+            let mut index = String::new();
+            while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                index.push(*d);
+                chars.next();
+            }
+
+            match chars.next() {
+                Some('%') if index.is_empty() => out.push('%'),
+                Some('s') if index.is_empty() => {
+                    out.push_str(args.get(next).map(|a| &a[..]).unwrap_or_default());
+                    next += 1;
+                }
+                Some('$') if chars.next_if_eq(&'s').is_some() => {
+                    let i = index.parse::<usize>().unwrap_or_default();
+                    out.push_str(
+                        args.get(i.wrapping_sub(1))
+                            .map(|a| &a[..])
+                            .unwrap_or_default(),
+                    );
+                }
+                other => {
+                    out.push('%');
+                    out.push_str(&index);
+                    out.extend(other);
+                }
+            }
+        }
+
+        out
+    }
+
+    fn write_plain(&self, out: &mut String, lang: Option<&HashMap<String, String>>) {
+        let content = self.content(lang);
+        let mut chars = content.chars();
+
+        while let Some(c) = chars.next() {
+            if c == '§' {
+                chars.next();
+            } else {
+                out.push(c);
+            }
+        }
+
+        for e in self.extra.iter().flatten() {
+            e.write_plain(out, lang);
+        }
+    }
+
+    fn write_ansi(&self, out: &mut String, parent: &AnsiStyle) {
+        let style = parent.inherit(self);
+        let mut current = style.clone();
+        out.push_str(&current.escape());
+
+        let content = self.content(None);
+        let mut chars = content.chars();
+
+        while let Some(c) = chars.next() {
+            if c == '§' {
+                if let Some(code) = chars.next() {
+                    current.apply_legacy(code);
+                    out.push_str(&current.escape());
+                }
+            } else {
+                out.push(c);
+            }
+        }
+
+        for e in self.extra.iter().flatten() {
+            e.write_ansi(out, &style);
+        }
+    }
+}
+
+/// Resolved style of a chat component, used for ANSI rendering.
+#[derive(Debug, Default, Clone)]
+struct AnsiStyle {
+    color: Option<u8>,
+    bold: bool,
+    italic: bool,
+    underlined: bool,
+    strikethrough: bool,
 }
 
-impl Default for Chat {
-    fn default() -> Self {
+impl AnsiStyle {
+    /// ANSI foreground codes of the 16 named colors, in legacy code order.
+    const COLORS: [(&'static str, u8); 16] = [
+        ("black", 30),
+        ("dark_blue", 34),
+        ("dark_green", 32),
+        ("dark_aqua", 36),
+        ("dark_red", 31),
+        ("dark_purple", 35),
+        ("gold", 33),
+        ("gray", 37),
+        ("dark_gray", 90),
+        ("blue", 94),
+        ("green", 92),
+        ("aqua", 96),
+        ("red", 91),
+        ("light_purple", 95),
+        ("yellow", 93),
+        ("white", 97),
+    ];
+
+    /// Style of a component, with unset fields taken from its parent.
+    fn inherit(&self, chat: &Chat) -> Self {
         Self {
-            text: None,
-            translate: None,
-            bold: None,
-            italic: None,
-            underlined: None,
-            strikethrough: None,
-            obfuscated: None,
-            color: None,
-            click_event: None,
-            hover_event: None,
-            extra: None,
+            color: match &chat.color {
+                Some(c) => Self::COLORS
+                    .iter()
+                    .find(|(n, _)| n == c)
+                    .map(|(_, a)| *a)
+                    .or(self.color),
+                _ => self.color,
+            },
+            bold: chat.bold.unwrap_or(self.bold),
+            italic: chat.italic.unwrap_or(self.italic),
+            underlined: chat.underlined.unwrap_or(self.underlined),
+            strikethrough: chat.strikethrough.unwrap_or(self.strikethrough),
+        }
+    }
+
+    /// Apply a legacy `§` formatting code.
+    fn apply_legacy(&mut self, code: char) {
+        match code.to_ascii_lowercase() {
+            'l' => self.bold = true,
+            'm' => self.strikethrough = true,
+            'n' => self.underlined = true,
+            'o' => self.italic = true,
+            'r' => *self = Self::default(),
+            c => {
+                // Colors also reset formatting.
+                if let Some(i) = c.to_digit(16) {
+                    *self = Self {
+                        color: Some(Self::COLORS[i as usize].1),
+                        ..Default::default()
+                    };
+                }
+            }
+        }
+    }
+
+    /// Escape sequence that resets the terminal and applies this style.
+    fn escape(&self) -> String {
+        let mut codes = String::from("\x1b[0");
+
+        for (set, code) in [
+            (self.bold, 1),
+            (self.italic, 3),
+            (self.underlined, 4),
+            (self.strikethrough, 9),
+        ]
+        .iter()
+        {
+            if *set {
+                codes.push_str(&format!(";{}", code));
+            }
+        }
+
+        if let Some(c) = self.color {
+            codes.push_str(&format!(";{}", c));
+        }
+
+        codes.push('m');
+        codes
+    }
+}
+
+/// Color of a chat component.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ChatColor {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+    /// RGB color, such as `#55ff55`. Only supported since 1.16.
+    Hex(u32),
+}
+
+impl ChatColor {
+    /// Names of the 16 named colors, in legacy code order.
+    const NAMES: [(&'static str, ChatColor); 16] = [
+        ("black", ChatColor::Black),
+        ("dark_blue", ChatColor::DarkBlue),
+        ("dark_green", ChatColor::DarkGreen),
+        ("dark_aqua", ChatColor::DarkAqua),
+        ("dark_red", ChatColor::DarkRed),
+        ("dark_purple", ChatColor::DarkPurple),
+        ("gold", ChatColor::Gold),
+        ("gray", ChatColor::Gray),
+        ("dark_gray", ChatColor::DarkGray),
+        ("blue", ChatColor::Blue),
+        ("green", ChatColor::Green),
+        ("aqua", ChatColor::Aqua),
+        ("red", ChatColor::Red),
+        ("light_purple", ChatColor::LightPurple),
+        ("yellow", ChatColor::Yellow),
+        ("white", ChatColor::White),
+    ];
+
+    /// Parse a color name or `#rrggbb` hex color.
+    pub fn parse(color: &str) -> Option<Self> {
+        match color.strip_prefix('#') {
+            Some(hex) if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                u32::from_str_radix(hex, 16).ok().map(Self::Hex)
+            }
+            Some(_) => None,
+            _ => Self::NAMES
+                .iter()
+                .find(|(n, _)| *n == color)
+                .map(|(_, c)| *c),
+        }
+    }
+
+    /// Get the name or `#rrggbb` hex string of the color, as used in chat components.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Hex(rgb) => format!("#{:06x}", rgb),
+            c => Self::NAMES
+                .iter()
+                .find(|(_, n)| n == c)
+                .map(|(n, _)| (*n).to_owned())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Color and formatting of a chat component.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ChatStyle {
+    pub color: Option<ChatColor>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub strikethrough: bool,
+    pub obfuscated: bool,
+}
+
+impl ChatStyle {
+    /// Set the color and formatting of a chat component to this style.
+    pub fn apply_to(&self, chat: &mut Chat) {
+        chat.color = self.color.map(|c| c.name());
+        chat.bold = Some(self.bold);
+        chat.italic = Some(self.italic);
+        chat.underlined = Some(self.underlined);
+        chat.strikethrough = Some(self.strikethrough);
+        chat.obfuscated = Some(self.obfuscated);
+    }
+}
+
+/// Where a chat message is displayed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ChatPosition {
+    /// Player chat box.
+    Chat,
+    /// System message in the chat box.
+    System,
+    /// Above the hotbar.
+    GameInfo,
+}
+
+/// Action performed by a digging player.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DiggingStatus {
+    StartDestroy,
+    CancelDestroy,
+    FinishDestroy,
+    DropItemStack,
+    DropItem,
+    /// Shoot an arrow or finish eating.
+    ShootArrowOrFinishEating,
+    SwapItemInHand,
+}
+
+/// Face of a block.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlockFace {
+    Bottom,
+    Top,
+    North,
+    South,
+    West,
+    East,
+}
+
+// ---- Block Changes ------
+
+/// Single block change within a chunk.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BlockChangeRecord {
+    /// X and Z coordinates relative to the chunk, packed as `x << 4 | z`.
+    pub horizontal_position: u8,
+    pub y_position: u8,
+    /// Block id and metadata, packed as `id << 4 | meta`.
+    pub block_state: i32,
+}
+
+impl BlockChangeRecord {
+    /// Get the X coordinate relative to the chunk.
+    #[inline]
+    pub fn relative_x(&self) -> u8 {
+        self.horizontal_position >> 4
+    }
+
+    /// Get the Y coordinate.
+    #[inline]
+    pub fn relative_y(&self) -> u8 {
+        self.y_position
+    }
+
+    /// Get the Z coordinate relative to the chunk.
+    #[inline]
+    pub fn relative_z(&self) -> u8 {
+        self.horizontal_position & 0xF
+    }
+}
+
+/// Action requested by a [`ClientStatus`](super::ClientStatus) event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ClientStatusAction {
+    PerformRespawn,
+    RequestStats,
+    /// Sent when the inventory is first opened. Only exists in 1.8.
+    TakingInventoryAchievement,
+}
+
+// ---- Boss Bars ----------
+
+/// Action of a [`BossBar`](super::BossBar) event.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BossBarAction {
+    Add {
+        title: Chat,
+        /// Health between 0 and 1.
+        health: f32,
+        color: i32,
+        division: i32,
+        /// Bit 0 darkens the sky, bit 1 plays end music and bit 2 creates fog.
+        flags: u8,
+    },
+    Remove,
+    UpdateHealth(f32),
+    UpdateTitle(Chat),
+    UpdateStyle {
+        color: i32,
+        division: i32,
+    },
+    UpdateFlags(u8),
+}
+
+// ---- Titles -------------
+
+/// Action of a [`Title`](super::Title) event.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TitleAction {
+    SetTitle(Chat),
+    /// Shown below the title.
+    SetSubtitle(Chat),
+    /// Shown above the hotbar.
+    SetActionBar(Chat),
+    /// Times are in ticks.
+    SetTimes {
+        fade_in: i32,
+        stay: i32,
+        fade_out: i32,
+    },
+    Hide,
+    /// Clear the title and subtitle, and reset the times to their defaults.
+    Reset,
+}
+
+// ---- Statistics ---------
+
+/// Kind of a [`Statistic`](super::Statistic), decided by its identifier.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StatCategory {
+    /// Blocks mined, `stat.mineBlock.*`.
+    Mined,
+    /// Items crafted, `stat.craftItem.*`.
+    Crafted,
+    /// Items used, `stat.useItem.*`.
+    Used,
+    /// Tools broken, `stat.breakItem.*`.
+    Broken,
+    /// Any other `stat.*` value, such as distance walked.
+    General,
+    /// Achievements and identifiers outside of the `stat` namespace.
+    Custom,
+}
+
+/// Hand used by the player.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Hand {
+    MainHand,
+    /// Only exists since 1.9.
+    OffHand,
+}
+
+/// Kind of an [`InteractEntity`](super::InteractEntity) event.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EntityInteraction {
+    Interact,
+    Attack,
+    /// Interact at a position relative to the entity.
+    InteractAt {
+        x: f32,
+        y: f32,
+        z: f32,
+    },
+}
+
+/// Equipment slot of an [`EntityEquipment`](super::EntityEquipment) event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EquipmentSlot {
+    Held,
+    Boots,
+    Leggings,
+    Chestplate,
+    Helmet,
+}
+
+/// Animation played by an [`EntityAnimation`](super::EntityAnimation) event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AnimationType {
+    SwingMainArm,
+    TakeDamage,
+    LeaveBed,
+    EatFood,
+    CriticalEffect,
+    MagicCriticalEffect,
+}
+
+/// Common status codes of an [`EntityStatus`](super::EntityStatus) event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EntityStatusKind {
+    Hurt,
+    Death,
+    /// Smoke particles shown when taming a wolf or ocelot fails.
+    TamingFailed,
+    /// Heart particles shown when taming a wolf or ocelot succeeds.
+    TamingSucceeded,
+    /// A wolf shaking off water.
+    ShakingWater,
+    /// The server accepted the player eating or drinking.
+    EatingFinished,
+    /// A sheep eating grass, or a TNT minecart being ignited.
+    EatingGrass,
+    /// Heart particles shown when an animal is bred.
+    InLove,
+    EnableReducedDebugInfo,
+    DisableReducedDebugInfo,
+}
+
+// ---- Time ---------------
+
+/// Part of the day of a [`TimeUpdate`](super::TimeUpdate) event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DayPhase {
+    /// Ticks `0` to `11999`.
+    Day,
+    /// Ticks `12000` to `12999`.
+    Sunset,
+    /// Ticks `13000` to `22999`.
+    Night,
+    /// Ticks `23000` to `23999`.
+    Sunrise,
+}
+
+// ---- Plugin Messages ----
+
+/// Decoded [`PluginMessage`](super::PluginMessage) data.
+#[derive(Debug, PartialEq, Clone)]
+pub enum KnownPluginMessage {
+    /// Name of the client or server implementation, such as `vanilla`.
+    Brand(String),
+    /// Message on a channel that isn't decoded.
+    Unknown { channel: String, data: Vec<u8> },
+}
+
+// ---- Chunks -------------
+
+/// Column of 16 chunk sections stacked vertically.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Chunk {
+    /// Sections from the bottom of the world up. Empty sections are `None`.
+    pub sections: Vec<Option<ChunkSection>>,
+    /// Biome of each block column, indexed by `z << 4 | x`.
+    pub biomes: Option<[u8; 256]>,
+}
+
+impl Chunk {
+    /// Get a bit mask of the sections that are present.
+    /// Fails if there are more than 16 sections.
+    pub fn primary_bit_mask(&self) -> TetsuResult<u16> {
+        if self.sections.len() > 16 {
+            return Err(Error::from(InvalidValue {
+                expected: format!("at most 16 sections, got {}", self.sections.len()),
+            }));
         }
+
+        Ok(self
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.is_some())
+            .fold(0, |mask, (i, _)| mask | 1 << i))
+    }
+
+    /// Get the block state at chunk relative coordinates, or `None` if its
+    /// section is empty or the coordinates are outside the chunk.
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> Option<u16> {
+        self.sections
+            .get(y >> 4)?
+            .as_ref()
+            .and_then(|s| s.get_block(x, y & 0xF, z))
+    }
+}
+
+/// 16x16x16 cube of blocks.
+///
+/// Blocks are indexed by `y << 8 | z << 4 | x`. Light levels are packed two
+/// to a byte, with the lower index in the low nibble.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChunkSection {
+    /// Block states, packed as `id << 4 | meta`.
+    pub blocks: Vec<u16>,
+    pub block_light: Vec<u8>,
+    /// Sky light, only sent in dimensions with a sky.
+    pub sky_light: Option<Vec<u8>>,
+}
+
+impl ChunkSection {
+    /// Get the block state at section relative coordinates, or `None` if any
+    /// coordinate is above 15.
+    #[inline]
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> Option<u16> {
+        self.blocks.get(Self::index(x, y, z)?).copied()
+    }
+
+    /// Get the block light level at section relative coordinates, or `None`
+    /// if any coordinate is above 15.
+    #[inline]
+    pub fn get_block_light(&self, x: usize, y: usize, z: usize) -> Option<u8> {
+        Self::nibble(&self.block_light, Self::index(x, y, z)?)
+    }
+
+    /// Get the sky light level at section relative coordinates, or `None` if
+    /// the section has no sky light or any coordinate is above 15.
+    #[inline]
+    pub fn get_sky_light(&self, x: usize, y: usize, z: usize) -> Option<u8> {
+        Self::nibble(self.sky_light.as_ref()?, Self::index(x, y, z)?)
+    }
+
+    #[inline]
+    fn index(x: usize, y: usize, z: usize) -> Option<usize> {
+        if x > 15 || y > 15 || z > 15 {
+            return None;
+        }
+        Some(y << 8 | z << 4 | x)
+    }
+
+    #[inline]
+    fn nibble(data: &[u8], index: usize) -> Option<u8> {
+        data.get(index >> 1).map(|b| (b >> ((index & 1) * 4)) & 0xF)
     }
 }
 
@@ -216,6 +911,7 @@ pub struct PlayerPositionAndLook {
     pub z: RelativeOrAbsolute<f64>,
     pub yaw: RelativeOrAbsolute<f32>,
     pub pitch: RelativeOrAbsolute<f32>,
+    /// Sent since 1.9. Must be confirmed with a [`TeleportConfirm`](super::TeleportConfirm).
     pub teleport_id: Option<i32>,
 }
 
@@ -228,3 +924,98 @@ pub struct Slot {
     pub damage: Option<i16>,
     pub nbt: Option<nbt::Blob>,
 }
+
+impl Slot {
+    /// Get the custom name of the item, if it has one.
+    pub fn display_name(&self) -> Option<String> {
+        match self.display()?.get("Name")? {
+            nbt::Value::String(name) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Get the lines of lore on the item.
+    pub fn lore(&self) -> Vec<String> {
+        match self.display().and_then(|d| d.get("Lore")) {
+            Some(nbt::Value::List(lines)) => lines
+                .iter()
+                .filter_map(|l| match l {
+                    nbt::Value::String(l) => Some(l.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Get the enchantment ids and levels on the item.
+    ///
+    /// Enchantments are stored under `ench` with numeric ids before 1.13, and
+    /// under `Enchantments` with namespaced ids after. Numeric ids are
+    /// returned as strings.
+    pub fn enchantments(&self) -> Vec<(String, i16)> {
+        let nbt = match &self.nbt {
+            Some(nbt) => nbt,
+            None => return vec![],
+        };
+
+        match nbt.get("Enchantments").or_else(|| nbt.get("ench")) {
+            Some(nbt::Value::List(enchantments)) => enchantments
+                .iter()
+                .filter_map(|e| match e {
+                    nbt::Value::Compound(e) => {
+                        let id = match e.get("id")? {
+                            nbt::Value::String(id) => id.clone(),
+                            nbt::Value::Short(id) => id.to_string(),
+                            _ => return None,
+                        };
+                        match e.get("lvl")? {
+                            nbt::Value::Short(lvl) => Some((id, *lvl)),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn display(&self) -> Option<&nbt::Map<String, nbt::Value>> {
+        match self.nbt.as_ref()?.get("display")? {
+            nbt::Value::Compound(display) => Some(display),
+            _ => None,
+        }
+    }
+}
+
+// ---- Entity Metadata ------
+
+/// Indexed fields describing an entity's state.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct EntityMetadata(pub Vec<MetadataEntry>);
+
+/// Single entity metadata field.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MetadataEntry {
+    pub index: u8,
+    pub value: MetadataValue,
+}
+
+/// Value of an entity metadata field.
+///
+/// Not every value type exists in every protocol version.
+#[derive(Debug, PartialEq, Clone)]
+pub enum MetadataValue {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    VarInt(i32),
+    Float(f32),
+    String(String),
+    Chat(Chat),
+    Slot(Slot),
+    Bool(bool),
+    Position(Position),
+    Rotation(f32, f32, f32),
+}
@@ -4,16 +4,14 @@ High level interface to Minecraft's server protocols.
 # Examples
 ```no_run
 use std::env;
-use std::thread;
-use std::time;
 
-use tetsu::errors;
 use tetsu::client;
 
 let user = client::mojang::User::authenticate(
     env::var("MOJANG_USER").unwrap(),
     env::var("MOJANG_USER_PWD").unwrap(),
-);
+)
+.unwrap();
 
 let mut client = client::Client::new("127.0.0.1", None, None).unwrap();
 client.connect_user(user).unwrap();
@@ -21,14 +19,7 @@ client.connect_user(user).unwrap();
 loop {
     match client.read_event() {
         Ok(e) => println!("{:?}", e),
-        Err(e) => match e {
-            errors::ConnectionError::LockError(_) => {
-                thread::sleep(time::Duration::from_millis(50));
-                continue;
-            }
-            errors::ConnectionError::Error(e) =>
-                panic!("Error while reading event: {:?}", e),
-        },
+        Err(e) => panic!("Error while reading event: {}", e),
     }
 }
 ```
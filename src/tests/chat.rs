@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use crate::event::{Chat, ChatColor, ChatStyle, ServerDescription};
+
+fn sample() -> Chat {
+    Chat {
+        text: Some("Hello ".to_owned()),
+        color: Some("gold".to_owned()),
+        bold: Some(true),
+        extra: Some(vec![
+            Chat {
+                text: Some("§aworld".to_owned()),
+                ..Default::default()
+            },
+            Chat {
+                translate: Some("chat.type.text".to_owned()),
+                bold: Some(false),
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_plain_string() {
+    assert_eq!(sample().to_plain_string(), "Hello world{chat.type.text}");
+}
+
+#[test]
+fn test_ansi_string() {
+    assert_eq!(
+        sample().to_ansi_string(),
+        "\x1b[0;1;33mHello \x1b[0;1;33m\x1b[0;92mworld\x1b[0;33m{chat.type.text}\x1b[0m"
+    );
+}
+
+#[test]
+fn test_translate_with() {
+    let mut lang = HashMap::new();
+    lang.insert("chat.type.text".to_owned(), "<%s> %s".to_owned());
+    lang.insert(
+        "death.attack.player".to_owned(),
+        "%2$s was slain by %1$s".to_owned(),
+    );
+
+    let text = |t: &str| Chat {
+        text: Some(t.to_owned()),
+        ..Default::default()
+    };
+    let translated = |key: &str, with| Chat {
+        translate: Some(key.to_owned()),
+        with: Some(with),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        translated("chat.type.text", vec![text("Notch"), text("hi 100%")]).translate_with(&lang),
+        "<Notch> hi 100%"
+    );
+    assert_eq!(
+        translated("death.attack.player", vec![text("jeb_"), text("Notch")]).translate_with(&lang),
+        "Notch was slain by jeb_"
+    );
+    assert_eq!(
+        translated("multiplayer.player.joined", vec![text("Notch")]).translate_with(&lang),
+        "multiplayer.player.joined"
+    );
+}
+
+#[test]
+fn test_style() {
+    let chat: Chat =
+        serde_json::from_str(r#"{"text": "Gold", "color": "gold", "bold": true}"#).unwrap();
+    let style = chat.style();
+    assert_eq!(
+        style,
+        ChatStyle {
+            color: Some(ChatColor::Gold),
+            bold: true,
+            ..Default::default()
+        }
+    );
+
+    let hex: Chat = serde_json::from_str(r##"{"text": "Green", "color": "#55ff55"}"##).unwrap();
+    assert_eq!(hex.style().color, Some(ChatColor::Hex(0x55ff55)));
+
+    // Re-applying the styles gives back the same colors.
+    let mut styled = Chat::default();
+    style.apply_to(&mut styled);
+    assert_eq!(styled.color.as_deref(), Some("gold"));
+    assert_eq!(styled.bold, Some(true));
+    assert_eq!(styled.italic, Some(false));
+
+    hex.style().apply_to(&mut styled);
+    assert_eq!(styled.color.as_deref(), Some("#55ff55"));
+    assert_eq!(styled.bold, Some(false));
+
+    assert_eq!(ChatColor::parse("not_a_color"), None);
+    assert_eq!(ChatColor::parse("#55ff5"), None);
+}
+
+#[test]
+fn test_server_description() {
+    let motd: ServerDescription = serde_json::from_str(
+        r#"{
+            "text": "",
+            "extra": [
+                {"text": "A ", "color": "gold", "bold": true},
+                {"text": "Minecraft", "color": "aqua", "extra": [{"text": " Server"}]}
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    match &motd {
+        ServerDescription::Long(chat) => {
+            assert_eq!(chat.to_plain_string(), "A Minecraft Server");
+
+            let extra = chat.extra.as_ref().unwrap();
+            assert_eq!(extra[0].style().color, Some(ChatColor::Gold));
+            assert_eq!(extra[1].color.as_deref(), Some("aqua"));
+        }
+        d => panic!("Expected a long description, got {:?}", d),
+    }
+
+    // The components survive being written back out.
+    let json = serde_json::to_string(&motd).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ServerDescription>(&json).unwrap(),
+        motd
+    );
+
+    assert_eq!(
+        serde_json::from_str::<ServerDescription>(r#""A Minecraft Server""#).unwrap(),
+        ServerDescription::Short("A Minecraft Server".to_owned())
+    );
+}
@@ -0,0 +1,832 @@
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::client::{connection::EncryptedConnection, proxy, Client, ClientBuilder, ProxyConfig};
+use crate::errors::Error;
+use crate::event::dispatcher::EventDispatcher;
+use crate::event::*;
+
+/// Spawn a single-connection server on a local port, handling it with `handler`.
+fn mock_server<F>(handler: F) -> (u16, thread::JoinHandle<()>)
+where
+    F: FnOnce(TcpStream, EventDispatcher<TcpStream, TcpStream>) + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        handler(stream, EventDispatcher::new(&ProtocolVersion::V47));
+    });
+
+    (port, handle)
+}
+
+fn server_information() -> ServerInformation {
+    ServerInformation {
+        description: ServerDescription::Short("A Minecraft Server".to_owned()),
+        players: ServerPlayers {
+            max: 20,
+            online: 1,
+            sample: None,
+        },
+        version: ServerVersion {
+            name: "1.8.9".to_owned(),
+            protocol: ProtocolVersion::V47,
+        },
+    }
+}
+
+#[test]
+fn test_read_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let mut connection = EncryptedConnection::new("127.0.0.1", port, ProtocolVersion::V47).unwrap();
+    // Accept but never write anything.
+    let _server = listener.accept().unwrap();
+
+    connection
+        .set_read_timeout(Some(Duration::from_millis(100)))
+        .unwrap();
+
+    let start = Instant::now();
+    match connection.read_event() {
+        Err(Error::Io(e)) => assert!(matches!(
+            e.kind(),
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+        )),
+        e => panic!("Expected a timeout, got {:?}", e),
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_client_builder() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // A fixed protocol skips detection, which would wait for a status response.
+    let client = ClientBuilder::new()
+        .address("127.0.0.1")
+        .port(port)
+        .protocol(ProtocolVersion::V47)
+        .read_timeout(Duration::from_millis(100))
+        .auto_keepalive(false)
+        .build()
+        .unwrap();
+    let _server = listener.accept().unwrap();
+
+    assert_eq!(client.get_server_address(), &format!("127.0.0.1:{}", port));
+
+    let start = Instant::now();
+    assert!(matches!(client.read_event(), Err(Error::Io(_))));
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    assert!(matches!(
+        Client::builder().port(port).build(),
+        Err(Error::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn test_raw_packets() {
+    let small = vec![1, 2, 3];
+    let large = vec![0xAB; 1024];
+
+    let (port, server) = {
+        let (small, large) = (small.clone(), large.clone());
+        mock_server(move |mut stream, dispatcher| {
+            let mut reader = stream.try_clone().unwrap();
+
+            for expected in [&small, &large].iter() {
+                match dispatcher
+                    .read_event(
+                        &mut reader,
+                        &EventState::Play,
+                        &EventDirection::ServerBound,
+                        64,
+                    )
+                    .unwrap()
+                {
+                    Event::Unknown { id, data, .. } => {
+                        assert_eq!(id, 0x7A);
+                        assert_eq!(&data, *expected);
+                    }
+                    e => panic!("Expected an unknown packet, got {:?}", e),
+                }
+            }
+
+            let writer = EventDispatcher::<TcpStream, TcpStream>::new(&ProtocolVersion::V47);
+            for data in [small, large].iter() {
+                writer
+                    .write_event(
+                        &mut stream,
+                        Event::Unknown {
+                            id: 0x7B,
+                            state: EventState::Play,
+                            direction: EventDirection::ClientBound,
+                            data: data.clone(),
+                        },
+                        &EventState::Play,
+                        &EventDirection::ClientBound,
+                        64,
+                    )
+                    .unwrap();
+            }
+        })
+    };
+
+    let mut connection = EncryptedConnection::new("127.0.0.1", port, ProtocolVersion::V47).unwrap();
+    connection.set_state(&EventState::Play);
+    connection.set_compression_threshold(64);
+
+    connection.send_raw(0x7A, &small).unwrap();
+    connection.send_raw(0x7A, &large).unwrap();
+
+    assert_eq!(connection.read_raw().unwrap(), (0x7B, small));
+    assert_eq!(connection.read_raw().unwrap(), (0x7B, large));
+    server.join().unwrap();
+}
+
+#[test]
+fn test_ping_server() {
+    let (port, server) = mock_server(|mut stream, dispatcher| {
+        let mut reader = stream.try_clone().unwrap();
+        let mut read = |state| {
+            dispatcher
+                .read_event(&mut reader, &state, &EventDirection::ServerBound, 0)
+                .unwrap()
+        };
+
+        assert!(matches!(read(EventState::Handshake), Event::Handshake(_)));
+        assert!(matches!(read(EventState::Status), Event::StatusRequest(_)));
+
+        let writer = EventDispatcher::<TcpStream, TcpStream>::new(&ProtocolVersion::V47);
+        let mut write = |event| {
+            writer
+                .write_event(
+                    &mut stream,
+                    event,
+                    &EventState::Status,
+                    &EventDirection::ClientBound,
+                    0,
+                )
+                .unwrap()
+        };
+
+        write(Event::StatusResponse(StatusResponse {
+            response: server_information(),
+        }));
+
+        match read(EventState::Status) {
+            Event::Ping(p) => write(Event::Pong(Pong { payload: p.payload })),
+            e => panic!("Expected Ping, got {:?}", e),
+        }
+    });
+
+    let (information, latency) = Client::ping_server("127.0.0.1", Some(port)).unwrap();
+    server.join().unwrap();
+
+    assert_eq!(information, server_information());
+    assert!(latency < Duration::from_secs(5));
+}
+
+/// Accept an offline mode login for the user "tetsu", leaving compression enabled.
+fn accept_offline_login(
+    stream: &mut TcpStream,
+    reader: &mut TcpStream,
+    dispatcher: &EventDispatcher<TcpStream, TcpStream>,
+) {
+    match dispatcher
+        .read_event(
+            reader,
+            &EventState::Handshake,
+            &EventDirection::ServerBound,
+            0,
+        )
+        .unwrap()
+    {
+        Event::Handshake(h) => assert_eq!(h.next_state, EventState::Login),
+        e => panic!("Expected Handshake, got {:?}", e),
+    }
+    match dispatcher
+        .read_event(reader, &EventState::Login, &EventDirection::ServerBound, 0)
+        .unwrap()
+    {
+        Event::LoginStart(l) => assert_eq!(l.name, "tetsu"),
+        e => panic!("Expected LoginStart, got {:?}", e),
+    }
+
+    // Offline servers enable compression and skip encryption.
+    let events = [
+        (Event::SetCompression(SetCompression { threshold: 256 }), 0),
+        (
+            Event::LoginSuccess(LoginSuccess {
+                uuid: crate::client::mojang::User::offline_uuid("tetsu"),
+                name: "tetsu".to_owned(),
+            }),
+            256,
+        ),
+    ];
+    for (event, threshold) in events.iter() {
+        dispatcher
+            .write_event(
+                stream,
+                event.clone(),
+                &EventState::Login,
+                &EventDirection::ClientBound,
+                *threshold,
+            )
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_offline_login() {
+    let (port, server) = mock_server(|mut stream, dispatcher| {
+        let mut reader = stream.try_clone().unwrap();
+        accept_offline_login(&mut stream, &mut reader, &dispatcher);
+    });
+
+    let mut client = Client::new("127.0.0.1", Some(port), Some(ProtocolVersion::V47)).unwrap();
+    assert_eq!(client.get_compression_threshold().unwrap(), 0);
+    client
+        .connect_user(crate::client::mojang::User::offline("tetsu".to_owned()))
+        .unwrap();
+    server.join().unwrap();
+
+    // Set by the SetCompression event sent during login.
+    assert_eq!(client.get_compression_threshold().unwrap(), 256);
+
+    assert_eq!(
+        client
+            .get_connected_user()
+            .as_ref()
+            .unwrap()
+            .selected_profile
+            .name,
+        "tetsu"
+    );
+}
+
+#[test]
+fn test_reconnect() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = thread::spawn(move || {
+        let dispatcher = EventDispatcher::new(&ProtocolVersion::V47);
+
+        // Log in once and drop the connection.
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = stream.try_clone().unwrap();
+        accept_offline_login(&mut stream, &mut reader, &dispatcher);
+        drop((stream, reader));
+
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = stream.try_clone().unwrap();
+        accept_offline_login(&mut stream, &mut reader, &dispatcher);
+        dispatcher
+            .write_event(
+                &mut stream,
+                Event::KeepAlive(KeepAlive { id: 1 }),
+                &EventState::Play,
+                &EventDirection::ClientBound,
+                256,
+            )
+            .unwrap();
+    });
+
+    let mut client = Client::new("127.0.0.1", Some(port), Some(ProtocolVersion::V47)).unwrap();
+    client.enable_auto_keepalive(false);
+    client
+        .connect_user(crate::client::mojang::User::offline("tetsu".to_owned()))
+        .unwrap();
+    assert!(client.read_event().is_err());
+
+    client.reconnect().unwrap();
+    assert_eq!(
+        client.read_event().unwrap(),
+        Event::KeepAlive(KeepAlive { id: 1 })
+    );
+    assert_eq!(client.get_compression_threshold().unwrap(), 256);
+    server.join().unwrap();
+}
+
+#[test]
+fn test_auto_keepalive() {
+    let (port, server) = mock_server(|mut stream, dispatcher| {
+        let mut reader = stream.try_clone().unwrap();
+        accept_offline_login(&mut stream, &mut reader, &dispatcher);
+
+        dispatcher
+            .write_event(
+                &mut stream,
+                Event::KeepAlive(KeepAlive { id: 1234 }),
+                &EventState::Play,
+                &EventDirection::ClientBound,
+                256,
+            )
+            .unwrap();
+
+        match dispatcher
+            .read_event(
+                &mut reader,
+                &EventState::Play,
+                &EventDirection::ServerBound,
+                256,
+            )
+            .unwrap()
+        {
+            Event::KeepAliveResponse(r) => assert_eq!(r.id, 1234),
+            e => panic!("Expected KeepAliveResponse, got {:?}", e),
+        }
+    });
+
+    let mut client = Client::new("127.0.0.1", Some(port), Some(ProtocolVersion::V47)).unwrap();
+    client
+        .connect_user(crate::client::mojang::User::offline("tetsu".to_owned()))
+        .unwrap();
+
+    // The event is still handed to the caller.
+    assert_eq!(
+        client.read_event().unwrap(),
+        Event::KeepAlive(KeepAlive { id: 1234 })
+    );
+    server.join().unwrap();
+}
+
+#[test]
+fn test_client_is_send_and_sync() {
+    // Clients can be shared between threads, such as through an `Arc`.
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Client>();
+}
+
+#[test]
+fn test_event_handlers() {
+    let (port, server) = mock_server(|mut stream, dispatcher| {
+        let mut reader = stream.try_clone().unwrap();
+        accept_offline_login(&mut stream, &mut reader, &dispatcher);
+
+        for event in [
+            Event::TimeUpdate(TimeUpdate {
+                world_age: 1,
+                time_of_day: 2,
+            }),
+            Event::HeldItemChange(HeldItemChange { slot: 3 }),
+            Event::Disconnect(Disconnect {
+                reason: Chat {
+                    text: Some("Bye".to_owned()),
+                    ..Default::default()
+                },
+            }),
+        ]
+        .iter()
+        {
+            dispatcher
+                .write_event(
+                    &mut stream,
+                    event.clone(),
+                    &EventState::Play,
+                    &EventDirection::ClientBound,
+                    256,
+                )
+                .unwrap();
+        }
+    });
+
+    let mut client = Client::new("127.0.0.1", Some(port), Some(ProtocolVersion::V47)).unwrap();
+    client
+        .connect_user(crate::client::mojang::User::offline("tetsu".to_owned()))
+        .unwrap();
+
+    let count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let handler_count = count.clone();
+    client.on(move |_| {
+        handler_count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    });
+
+    // Returns once the disconnect has been handled.
+    client.run_event_loop().unwrap();
+    server.join().unwrap();
+    assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_read_event_of() {
+    let join = JoinGame {
+        id: 1,
+        gamemode: Gamemode::Survival,
+        dimension: Some(Dimension::Overworld),
+        difficulty: Some(Difficulty::Easy),
+        max_players: 20,
+        level_type: Some("default".to_owned()),
+        ..Default::default()
+    };
+    let sent = join.clone();
+
+    let (port, server) = mock_server(move |mut stream, dispatcher| {
+        let mut reader = stream.try_clone().unwrap();
+        accept_offline_login(&mut stream, &mut reader, &dispatcher);
+
+        for event in [Event::KeepAlive(KeepAlive { id: 1 }), Event::JoinGame(sent)].iter() {
+            dispatcher
+                .write_event(
+                    &mut stream,
+                    event.clone(),
+                    &EventState::Play,
+                    &EventDirection::ClientBound,
+                    256,
+                )
+                .unwrap();
+        }
+    });
+
+    let mut client = Client::new("127.0.0.1", Some(port), Some(ProtocolVersion::V47)).unwrap();
+    client.enable_auto_keepalive(false);
+    client
+        .connect_user(crate::client::mojang::User::offline("tetsu".to_owned()))
+        .unwrap();
+
+    assert_eq!(client.read_event_of::<JoinGame>().unwrap(), join);
+    server.join().unwrap();
+}
+
+#[test]
+fn test_try_read_event() {
+    use std::io::Write;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let client = Client::new("127.0.0.1", Some(port), Some(ProtocolVersion::V47)).unwrap();
+    let (mut stream, _) = listener.accept().unwrap();
+
+    let dispatcher = EventDispatcher::<io::Cursor<Vec<u8>>, Vec<u8>>::new(&ProtocolVersion::V47);
+    let mut frames = Vec::new();
+    for payload in [1, 2].iter() {
+        dispatcher
+            .write_event(
+                &mut frames,
+                Event::Pong(Pong { payload: *payload }),
+                &EventState::Status,
+                &EventDirection::ClientBound,
+                0,
+            )
+            .unwrap();
+    }
+    let first_len = frames.len() / 2;
+
+    let poll = || {
+        let start = Instant::now();
+        loop {
+            if let Some(e) = client.try_read_event().unwrap() {
+                return e;
+            }
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(5));
+        }
+    };
+
+    assert_eq!(client.try_read_event().unwrap(), None);
+
+    // Half of the first packet.
+    stream.write_all(&frames[..first_len / 2]).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(client.try_read_event().unwrap(), None);
+
+    // Rest of the first packet and the start of the second.
+    stream
+        .write_all(&frames[first_len / 2..first_len + 1])
+        .unwrap();
+    assert_eq!(poll(), Event::Pong(Pong { payload: 1 }));
+    assert_eq!(client.try_read_event().unwrap(), None);
+
+    stream.write_all(&frames[first_len + 1..]).unwrap();
+    assert_eq!(poll(), Event::Pong(Pong { payload: 2 }));
+}
+
+#[test]
+fn test_read_available_events() {
+    use std::io::Write;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let client = Client::new("127.0.0.1", Some(port), Some(ProtocolVersion::V47)).unwrap();
+    let (mut stream, _) = listener.accept().unwrap();
+
+    assert!(client.read_available_events().unwrap().is_empty());
+
+    let dispatcher = EventDispatcher::<io::Cursor<Vec<u8>>, Vec<u8>>::new(&ProtocolVersion::V47);
+    let mut frames = Vec::new();
+    for payload in [1, 2, 3].iter() {
+        dispatcher
+            .write_event(
+                &mut frames,
+                Event::Pong(Pong { payload: *payload }),
+                &EventState::Status,
+                &EventDirection::ClientBound,
+                0,
+            )
+            .unwrap();
+    }
+    stream.write_all(&frames).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    assert_eq!(
+        client.read_available_events().unwrap(),
+        vec![
+            Event::Pong(Pong { payload: 1 }),
+            Event::Pong(Pong { payload: 2 }),
+            Event::Pong(Pong { payload: 3 }),
+        ]
+    );
+    assert!(client.read_available_events().unwrap().is_empty());
+
+    // Events read before an error are kept, and the error is returned next.
+    let mut frames = Vec::new();
+    dispatcher
+        .write_event(
+            &mut frames,
+            Event::Pong(Pong { payload: 4 }),
+            &EventState::Status,
+            &EventDirection::ClientBound,
+            0,
+        )
+        .unwrap();
+    // Pong with a truncated payload.
+    frames.extend_from_slice(&[0x03, 0x01, 0x00, 0x00]);
+    stream.write_all(&frames).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    assert_eq!(
+        client.read_available_events().unwrap(),
+        vec![Event::Pong(Pong { payload: 4 })]
+    );
+    assert!(client.read_available_events().is_err());
+    assert!(client.read_available_events().unwrap().is_empty());
+}
+
+/// Reader returning one byte per call, with interruptions in between.
+struct Trickle {
+    data: Vec<u8>,
+    position: usize,
+    calls: usize,
+}
+
+impl io::Read for Trickle {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.calls += 1;
+        match self.calls % 3 {
+            0 => Err(io::ErrorKind::Interrupted.into()),
+            1 if self.position > 0 && self.position < self.data.len() => {
+                Err(io::ErrorKind::WouldBlock.into())
+            }
+            _ if self.position == self.data.len() => Ok(0),
+            _ => {
+                buf[0] = self.data[self.position];
+                self.position += 1;
+                Ok(1)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_frame_reader() {
+    use crate::client::connection::FrameReader;
+
+    let dispatcher = EventDispatcher::<io::Cursor<Vec<u8>>, Vec<u8>>::new(&ProtocolVersion::V47);
+    let mut data = Vec::new();
+    let events = [
+        Event::Pong(Pong { payload: -1 }),
+        Event::StatusResponse(StatusResponse {
+            response: server_information(),
+        }),
+    ];
+    for event in events.iter() {
+        dispatcher
+            .write_event(
+                &mut data,
+                event.clone(),
+                &EventState::Status,
+                &EventDirection::ClientBound,
+                0,
+            )
+            .unwrap();
+    }
+
+    let mut reader = Trickle {
+        data,
+        position: 0,
+        calls: 0,
+    };
+    let mut frames = FrameReader::default();
+
+    for event in events.iter() {
+        // Only a packet that hasn't started arriving can time out.
+        let frame = loop {
+            match frames.read_frame(&mut reader) {
+                Err(Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                r => break r.unwrap(),
+            }
+        };
+        let read = dispatcher
+            .read_event(
+                &mut io::Cursor::new(frame),
+                &EventState::Status,
+                &EventDirection::ClientBound,
+                0,
+            )
+            .unwrap();
+        assert_eq!(&read, event);
+    }
+
+    match frames.read_frame(&mut reader) {
+        Err(Error::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+        r => panic!("Expected UnexpectedEof, got {:?}", r),
+    }
+
+    // A negative length is rejected before anything is buffered for it.
+    let mut negative = io::Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+    match FrameReader::default().read_frame(&mut negative) {
+        Err(Error::InvalidValue(_)) => {}
+        r => panic!("Expected InvalidValue, got {:?}", r),
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_async_connection() {
+    use crate::client::connection::AsyncEncryptedConnection;
+    use crate::crypto::AsyncEncryptedStream;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let (client_end, server_end) = tokio::io::duplex(4096);
+        let mut server = AsyncEncryptedStream::new(server_end);
+        let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, Vec<u8>> =
+            EventDispatcher::new(&ProtocolVersion::V47);
+
+        let mut client = AsyncEncryptedConnection::from_stream(client_end, ProtocolVersion::V47);
+
+        let handshake = Event::Handshake(Handshake {
+            protocol_version: Some(47),
+            server_address: "localhost".to_owned(),
+            server_port: 25565,
+            next_state: EventState::Login,
+        });
+        client.set_state(&EventState::Handshake);
+        client.send_event(handshake.clone()).await.unwrap();
+
+        let mut buf = vec![0; 64];
+        let read = server.read(&mut buf).await.unwrap();
+        let received = dispatcher
+            .read_event(
+                &mut io::Cursor::new(buf[..read].to_vec()),
+                &EventState::Handshake,
+                &EventDirection::ServerBound,
+                0,
+            )
+            .unwrap();
+        assert_eq!(received, handshake);
+
+        // Both directions are encrypted after the cipher is set.
+        let key = [7; 16];
+        client.set_cipher(&key).unwrap();
+        server.set_cipher(&key).unwrap();
+        client.set_state(&EventState::Login);
+
+        let disconnect = Event::Disconnect(Disconnect {
+            reason: Chat {
+                text: Some("Bye".to_owned()),
+                ..Default::default()
+            },
+        });
+        let mut frame = Vec::new();
+        dispatcher
+            .write_event(
+                &mut frame,
+                disconnect.clone(),
+                &EventState::Login,
+                &EventDirection::ClientBound,
+                0,
+            )
+            .unwrap();
+        server.write_all(&frame).await.unwrap();
+        server.flush().await.unwrap();
+
+        assert_eq!(client.read_event().await.unwrap(), disconnect);
+    });
+}
+
+#[test]
+fn test_game_state_tracker() {
+    use crate::client::state::GameStateTracker;
+
+    let mut state = GameStateTracker::new();
+    assert_eq!(state.gamemode(), None);
+
+    state.update(&Event::JoinGame(JoinGame {
+        gamemode: Gamemode::Survival,
+        ..Default::default()
+    }));
+    assert_eq!(state.gamemode(), Some(&Gamemode::Survival));
+
+    let events = [
+        ChangeGameState::GamemodeUpdate(Gamemode::Creative),
+        ChangeGameState::BeginRaining,
+        ChangeGameState::RainLevel(0.5),
+        ChangeGameState::ThunderLevel(0.25),
+    ];
+    for e in events.iter() {
+        state.update(&Event::ChangeGameState(e.clone()));
+    }
+    assert_eq!(state.gamemode(), Some(&Gamemode::Creative));
+    assert!(state.is_raining());
+    assert_eq!(state.rain_level(), 0.5);
+    assert_eq!(state.thunder_level(), 0.25);
+
+    state.update(&Event::PlayerAbility(PlayerAbility {
+        invulnerable: true,
+        is_flying: true,
+        allow_flying: true,
+        creative_mode: true,
+        flying_speed: 0.05,
+        walking_speed: 0.1,
+    }));
+    assert!(state.is_flying());
+    assert!(state.is_invulnerable());
+
+    // Unrelated events are ignored.
+    state.update(&Event::KeepAlive(KeepAlive { id: 1 }));
+    assert!(state.is_raining());
+
+    state.update(&Event::ChangeGameState(ChangeGameState::EndRaining));
+    assert!(!state.is_raining());
+}
+
+/// Accept a single SOCKS5 connection requiring `user`/`pass`, then echo
+/// everything sent through the tunnel. Returns the requested target.
+fn socks5_responder(listener: std::net::TcpListener) -> (Vec<u8>, u16) {
+    use std::io::{Read, Write};
+
+    let (mut stream, _) = listener.accept().unwrap();
+
+    let mut greeting = [0; 3];
+    stream.read_exact(&mut greeting).unwrap();
+    assert_eq!(greeting, [0x05, 0x01, 0x02]);
+    stream.write_all(&[0x05, 0x02]).unwrap();
+
+    let mut auth = [0; 11];
+    stream.read_exact(&mut auth).unwrap();
+    assert_eq!(&auth, b"\x01\x04user\x04pass");
+    stream.write_all(&[0x01, 0x00]).unwrap();
+
+    let mut header = [0; 5];
+    stream.read_exact(&mut header).unwrap();
+    assert_eq!(header[..4], [0x05, 0x01, 0x00, 0x03]);
+    let mut host = vec![0; header[4] as usize];
+    stream.read_exact(&mut host).unwrap();
+    let mut port = [0; 2];
+    stream.read_exact(&mut port).unwrap();
+
+    stream
+        .write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0x63, 0xDD])
+        .unwrap();
+
+    let mut data = [0; 5];
+    stream.read_exact(&mut data).unwrap();
+    stream.write_all(&data).unwrap();
+
+    (host, u16::from_be_bytes(port))
+}
+
+#[test]
+fn test_connect_via_proxy() {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let proxy = ProxyConfig::new(&listener.local_addr().unwrap().to_string())
+        .with_credentials("user", "pass");
+    let responder = std::thread::spawn(move || socks5_responder(listener));
+
+    let mut stream = proxy::connect("mc.example.com:25565", &proxy).unwrap();
+    stream.write_all(b"hello").unwrap();
+    let mut echo = [0; 5];
+    stream.read_exact(&mut echo).unwrap();
+    assert_eq!(&echo, b"hello");
+
+    let (host, port) = responder.join().unwrap();
+    assert_eq!(host, b"mc.example.com");
+    assert_eq!(port, 25565);
+}
@@ -0,0 +1,94 @@
+use std::error::Error as _;
+use std::io;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+use crate::client::Client;
+use crate::crypto::DefaultStreamCipher;
+use crate::errors::*;
+use crate::event::{Event, ProtocolVersion, StatusRequest};
+
+#[test]
+fn test_error_display() {
+    let errors = vec![
+        (
+            Error::from(io::Error::other("socket closed")),
+            "socket closed",
+        ),
+        (
+            Error::from(String::from_utf8(vec![0xFF]).unwrap_err()),
+            "UTF-8",
+        ),
+        (
+            Error::from(serde_json::from_str::<i32>("x").unwrap_err()),
+            "JSON",
+        ),
+        (
+            Error::from(nbt::Blob::from_reader(&mut &[0xFFu8][..]).unwrap_err()),
+            "NBT",
+        ),
+        #[cfg(feature = "openssl")]
+        (
+            Error::from(openssl::rsa::Rsa::private_key_from_pem(b"not a key").unwrap_err()),
+            "OpenSSL",
+        ),
+        (
+            DefaultStreamCipher::new(&[0; 3]).err().unwrap(),
+            "key length",
+        ),
+        (
+            Error::from(InvalidValue {
+                expected: "0, 1".to_owned(),
+            }),
+            "0, 1",
+        ),
+        (Error::Auth("bad token".to_owned()), "bad token"),
+    ];
+
+    for (error, cause) in errors {
+        let message = error.to_string();
+        assert!(
+            message.contains(cause),
+            "{:?} formatted as {:?}",
+            error,
+            message
+        );
+    }
+
+    let error = Error::from(io::Error::other("socket closed"));
+    assert_eq!(error.source().unwrap().to_string(), "socket closed");
+    assert!(Error::Auth(String::new()).source().is_none());
+}
+
+#[test]
+fn test_lock_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let client =
+        Arc::new(Client::new("127.0.0.1", Some(port), Some(ProtocolVersion::V47)).unwrap());
+
+    // Panic while the connection is locked.
+    let poisoned = client.clone();
+    let _ = thread::spawn(move || {
+        let _guard = poisoned.connection.lock().unwrap();
+        panic!("poison the lock");
+    })
+    .join();
+
+    assert!(matches!(
+        client.send_event(Event::StatusRequest(StatusRequest {})),
+        Err(Error::Lock(_))
+    ));
+    assert!(matches!(client.try_read_event(), Err(Error::Lock(_))));
+    assert!(matches!(client.run_event_loop(), Err(Error::Lock(_))));
+
+    let error = client.read_event().unwrap_err();
+    assert!(matches!(error, Error::Lock(_)));
+    assert!(error.to_string().contains("poisoned"));
+    assert!(error.source().is_none());
+
+    // Owned errors can be boxed like any other error.
+    let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(error);
+    assert!(!boxed.to_string().is_empty());
+}
@@ -1,2 +1,7 @@
+pub mod chat;
+pub mod client;
 pub mod crypto;
+pub mod errors;
+pub mod mojang;
 pub mod serialization;
+pub mod types;
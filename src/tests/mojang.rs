@@ -0,0 +1,29 @@
+use crate::client::mojang::*;
+use crate::errors::Error;
+
+#[test]
+fn test_auth_error_status() {
+    let body = r#"{"error":"ForbiddenOperationException","errorMessage":"Invalid credentials."}"#;
+    let response = ureq::Response::new(403, "Forbidden", body).unwrap();
+
+    match read_response(Err(ureq::Error::Status(403, response))) {
+        Err(Error::Auth(e)) => assert!(e.contains("403") && e.contains("Invalid credentials")),
+        e => panic!("Expected Auth error, got {:?}", e),
+    }
+}
+
+#[test]
+fn test_offline_user() {
+    let user = User::offline("Notch".to_owned());
+
+    assert!(user.is_offline());
+    assert_eq!(user.selected_profile.name, "Notch");
+    assert_eq!(
+        User::offline_uuid("Notch").to_hyphenated().to_string(),
+        "b50ad385-829d-3141-a216-7e7d7539ba7f"
+    );
+    assert_eq!(user.selected_profile.id, "b50ad385829d3141a2167e7d7539ba7f");
+
+    // Offline users never contact the session server.
+    user.join_server("", &[0; 16], &[]).unwrap();
+}
@@ -1,18 +1,98 @@
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
+use std::sync::Once;
 
 use serde_json::json;
 
-use crate::event::Chat;
+use crate::errors::Error;
 use crate::event::{
     dispatcher::EventDispatcher, Disconnect, Event, EventDirection, EventState, Handshake,
-    ProtocolVersion,
+    LoginStart, LoginSuccess, PlayerPositionAndLook, ProtocolVersion, RelativeOrAbsolute,
+    SendChatMessage, SetCompression,
 };
+use crate::event::{AfterGameWin, ChangeGameState, DemoEventAction};
+use crate::event::{
+    Angle, DestroyEntities, EntityHeadLook, EntityLookAndRelativeMove, EntityMetadata,
+    EntityRelativeMove, EntityTeleport, EntityVelocity, MetadataEntry, MetadataValue, SpawnMob,
+    SpawnPlayer,
+};
+use crate::event::{Animation, AnimationType, EntityAnimation, Hand};
+use crate::event::{AttachEntity, EntityStatus, EntityStatusKind, Explosion};
+use crate::event::{
+    BlockChange, BlockChangeRecord, Chunk, ChunkSection, MapChunkBulk, MultiBlockChange,
+};
+use crate::event::{BlockFace, DiggingStatus, PlayerBlockPlacement, PlayerDigging, Position, Slot};
+use crate::event::{BossBar, BossBarAction, Title, TitleAction};
+use crate::event::{Camera, CollectItem, EntityEquipment, EquipmentSlot, Spectate};
+use crate::event::{Chat, ChatPosition, Difficulty, Dimension, Gamemode, JoinGame};
+use crate::event::{
+    ClickWindow, CloseWindow, ConfirmTransaction, OpenWindow, SlotUpdate, WindowProperty,
+};
+use crate::event::{
+    ClientSettings, ClientStatus, ClientStatusAction, PlayerLook, PlayerPosition,
+    PlayerPositionAndLookServerbound, Respawn, TabComplete, TabCompleteResponse, TeleportConfirm,
+    UpdateHealth,
+};
+use crate::event::{EntityInteraction, InteractEntity, SetHeldItem};
+use crate::event::{NamedSoundEffect, SetExperience};
+use crate::serialization::{Readable, Writable};
+use crate::versions::common::{VarInt, MAX_PACKET_LEN};
 
 const SER_RUNS: usize = 12_000;
 
+thread_local! {
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    static CAPTURED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Logger printing debug records like `env_logger`, which also records trace
+/// messages from threads inside [`capture_logs`].
+struct CaptureLogger(env_logger::Logger);
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        CAPTURING.with(|c| c.get()) || self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if CAPTURING.with(|c| c.get()) && record.target().starts_with("tetsu") {
+            CAPTURED.with(|c| c.borrow_mut().push(record.args().to_string()));
+        }
+        self.0.log(record);
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+/// Install the test logger. Only the first call has any effect.
+fn init_logger() {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        let inner = env_logger::builder()
+            .filter(Some("tetsu"), log::LevelFilter::Debug)
+            .build();
+        log::set_logger(Box::leak(Box::new(CaptureLogger(inner)))).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+}
+
+/// Run `f`, returning the messages it logged on this thread.
+fn capture_logs<F: FnOnce()>(f: F) -> Vec<String> {
+    init_logger();
+
+    CAPTURING.with(|c| c.set(true));
+    f();
+    CAPTURING.with(|c| c.set(false));
+
+    CAPTURED.with(|c| c.borrow_mut().drain(..).collect())
+}
+
 fn test_protocol_version(
     version: String,
     dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>>,
@@ -35,7 +115,7 @@ fn test_protocol_version(
             dispatcher.write_event(&mut buf, event_w, s, d, 0).unwrap();
 
             times
-                .get_mut(&name.to_string())
+                .get_mut(*name)
                 .unwrap()
                 .0
                 .push(start.elapsed().as_nanos() as u64);
@@ -50,7 +130,7 @@ fn test_protocol_version(
             let event_r = dispatcher.read_event(&mut buf, s, d, 0).unwrap();
 
             times
-                .get_mut(&name.to_string())
+                .get_mut(*name)
                 .unwrap()
                 .1
                 .push(start.elapsed().as_nanos() as u64);
@@ -69,13 +149,12 @@ fn test_protocol_version(
 
 #[test]
 fn test_event_serialization() {
-    env_logger::builder()
-        .filter(Some("tetsu"), log::LevelFilter::Debug)
-        .init();
+    init_logger();
 
     let events = [
         (
             Event::Handshake(Handshake {
+                protocol_version: Some(47),
                 server_address: "127.0.0.1".to_owned(),
                 server_port: 25565,
                 next_state: EventState::Status,
@@ -97,6 +176,20 @@ fn test_event_serialization() {
         ),
     ];
 
+    for version in [ProtocolVersion::V47, ProtocolVersion::V754].iter() {
+        let (handshake, state, direction, _) = &events[0];
+        roundtrip_event(
+            *version,
+            handshake.clone(),
+            *state,
+            *direction,
+            Some("0f002f093132372e302e302e3163dd01"),
+        );
+
+        let (disconnect, state, direction, _) = &events[1];
+        roundtrip_event(*version, disconnect.clone(), *state, *direction, None);
+    }
+
     test_protocol_version(
         "47".to_owned(),
         EventDispatcher::new(&ProtocolVersion::V47),
@@ -108,3 +201,2126 @@ fn test_event_serialization() {
         &events,
     );
 }
+
+/// Write an event and read it back with the given compression threshold,
+/// returning the written bytes and the read event.
+fn roundtrip_with_threshold(
+    version: ProtocolVersion,
+    event: Event,
+    state: EventState,
+    direction: EventDirection,
+    compression_threshold: i32,
+) -> (Vec<u8>, Event) {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&version);
+    let mut buf = io::Cursor::new(Vec::new());
+
+    dispatcher
+        .write_event(&mut buf, event, &state, &direction, compression_threshold)
+        .unwrap();
+    buf.set_position(0);
+
+    let read = dispatcher
+        .read_event(&mut buf, &state, &direction, compression_threshold)
+        .unwrap();
+    assert_eq!(buf.position() as usize, buf.get_ref().len());
+    (buf.into_inner(), read)
+}
+
+/// Write an event and read it back, returning the written bytes and the read event.
+fn roundtrip(
+    version: ProtocolVersion,
+    event: Event,
+    state: EventState,
+    direction: EventDirection,
+) -> (Vec<u8>, Event) {
+    roundtrip_with_threshold(version, event, state, direction, 0)
+}
+
+/// Round trip an event with compression both off and on, asserting that it
+/// reads back unchanged. If `expected` is given, the uncompressed bytes must
+/// match it as a lowercase hex string.
+fn roundtrip_event(
+    version: ProtocolVersion,
+    event: Event,
+    state: EventState,
+    direction: EventDirection,
+    expected: Option<&str>,
+) {
+    let (bytes, read) = roundtrip_with_threshold(version, event.clone(), state, direction, 0);
+    assert_eq!(read, event);
+
+    if let Some(expected) = expected {
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, expected);
+    }
+
+    let (_, read) = roundtrip_with_threshold(version, event.clone(), state, direction, 1);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_packet_trace_logging() {
+    let handshake = Event::Handshake(Handshake {
+        protocol_version: Some(47),
+        server_address: "127.0.0.1".to_owned(),
+        server_port: 25565,
+        next_state: EventState::Status,
+    });
+
+    let logs = capture_logs(|| {
+        roundtrip(
+            ProtocolVersion::V47,
+            handshake,
+            EventState::Handshake,
+            EventDirection::ServerBound,
+        );
+    });
+    assert_eq!(
+        logs,
+        [
+            "Wrote packet 0x00 (ServerBound, Handshake), 15 bytes",
+            "Read packet 0x00 (ServerBound, Handshake), 15 bytes",
+        ]
+    );
+
+    // Unknown packets are logged with their id too.
+    let logs = capture_logs(|| {
+        roundtrip(
+            ProtocolVersion::V47,
+            Event::Unknown {
+                id: 0x7F,
+                state: EventState::Play,
+                direction: EventDirection::ClientBound,
+                data: vec![1, 2, 3],
+            },
+            EventState::Play,
+            EventDirection::ClientBound,
+        );
+    });
+    assert_eq!(
+        logs,
+        [
+            "Wrote packet 0x7f (ClientBound, Play), 4 bytes",
+            "Read packet 0x7f (ClientBound, Play), 4 bytes",
+        ]
+    );
+}
+
+#[test]
+fn test_position_and_look_flags() {
+    let event = Event::PlayerPositionAndLook(PlayerPositionAndLook {
+        x: RelativeOrAbsolute::Relative(1.0),
+        y: RelativeOrAbsolute::Absolute(64.0),
+        z: RelativeOrAbsolute::Relative(-1.0),
+        yaw: RelativeOrAbsolute::Absolute(90.0),
+        pitch: RelativeOrAbsolute::Relative(10.0),
+        teleport_id: None,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+
+    assert_eq!(*bytes.last().unwrap(), 0b00010101);
+    assert_eq!(read, event);
+}
+
+/// Frame a raw packet body with its id and length prefix.
+fn frame(id: i32, body: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    VarInt(id).write_to(&mut packet).unwrap();
+    packet.extend_from_slice(body);
+
+    let mut framed = Vec::new();
+    VarInt(packet.len() as i32).write_to(&mut framed).unwrap();
+    framed.extend_from_slice(&packet);
+    framed
+}
+
+#[test]
+fn test_unknown_discriminants() {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+
+    // JoinGame with gamemode 5.
+    let mut body = vec![0, 0, 0, 1, 5, 0, 1, 20];
+    "default".to_owned().write_to(&mut body).unwrap();
+    body.push(0);
+
+    // PlayerInfoUpdate with action 9 for a single player.
+    let mut info = vec![9, 1];
+    info.extend_from_slice(&[0; 16]);
+
+    let malformed = [frame(0x01, &body), frame(0x38, &info)];
+
+    for f in malformed.iter() {
+        let mut buf = io::Cursor::new(f.clone());
+        match dispatcher.read_event(&mut buf, &EventState::Play, &EventDirection::ClientBound, 0) {
+            Err(Error::InvalidValue(_)) => {}
+            e => panic!("Expected InvalidValue, got {:?}", e),
+        }
+    }
+}
+
+#[test]
+fn test_oversized_wire_lengths() {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    let negative = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+
+    let mut too_long = Vec::new();
+    VarInt(MAX_PACKET_LEN as i32 + 1)
+        .write_to(&mut too_long)
+        .unwrap();
+
+    let malformed = [
+        // Frame lengths.
+        (negative.to_vec(), 0),
+        (too_long, 0),
+        // Decompressed length.
+        ([&[0x06][..], &negative, &[0x78]].concat(), 256),
+        // Statistics count.
+        (frame(0x37, &negative), 0),
+        // WindowItemsUpdate slot count.
+        (frame(0x30, &[0, 0xFF, 0xFF]), 0),
+    ];
+
+    for (f, compression_threshold) in malformed.iter() {
+        let mut buf = io::Cursor::new(f.clone());
+        match dispatcher.read_event(
+            &mut buf,
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            *compression_threshold,
+        ) {
+            Err(Error::InvalidValue(_)) => {}
+            e => panic!("Expected InvalidValue, got {:?}", e),
+        }
+    }
+}
+
+#[test]
+fn test_login_success_uuid() {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    let login_success = |uuid: &str| {
+        let mut body = Vec::new();
+        uuid.to_owned().write_to(&mut body).unwrap();
+        "tetsu".to_owned().write_to(&mut body).unwrap();
+
+        let mut buf = io::Cursor::new(frame(0x02, &body));
+        dispatcher.read_event(
+            &mut buf,
+            &EventState::Login,
+            &EventDirection::ClientBound,
+            0,
+        )
+    };
+    let expected = uuid::Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5);
+
+    for uuid in [
+        "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+        "069a79f444e94726a5befca90e38aaf5",
+    ]
+    .iter()
+    {
+        match login_success(uuid).unwrap() {
+            Event::LoginSuccess(l) => assert_eq!(l.uuid, expected),
+            e => panic!("Expected LoginSuccess, got {:?}", e),
+        }
+    }
+
+    assert!(matches!(
+        login_success("not-a-uuid"),
+        Err(Error::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn test_handshake_protocol_version() {
+    let handshake = |protocol_version| {
+        Event::Handshake(Handshake {
+            protocol_version,
+            server_address: "127.0.0.1".to_owned(),
+            server_port: 25565,
+            next_state: EventState::Login,
+        })
+    };
+
+    for version in [
+        ProtocolVersion::V47,
+        ProtocolVersion::V340,
+        ProtocolVersion::V754,
+    ]
+    .iter()
+    {
+        roundtrip_event(
+            *version,
+            handshake(Some(340)),
+            EventState::Handshake,
+            EventDirection::ServerBound,
+            None,
+        );
+
+        let (_, read) = roundtrip(
+            *version,
+            handshake(None),
+            EventState::Handshake,
+            EventDirection::ServerBound,
+        );
+        assert_eq!(read, handshake(Some(*version as i32)));
+    }
+}
+
+#[test]
+fn test_auto_impl_varint_field() {
+    let event = Event::SetCompression(SetCompression { threshold: 256 });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Login,
+        EventDirection::ClientBound,
+    );
+
+    // Length, packet id, then the threshold as a 2 byte VarInt.
+    assert_eq!(bytes, vec![0x03, 0x03, 0x80, 0x02]);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_chat_message() {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+
+    let mut body = Vec::new();
+    r#"{"text":"<Notch> ","extra":[{"text":"hello","color":"yellow"}]}"#
+        .to_owned()
+        .write_to(&mut body)
+        .unwrap();
+    body.push(0);
+
+    let mut buf = io::Cursor::new(frame(0x02, &body));
+    match dispatcher
+        .read_event(&mut buf, &EventState::Play, &EventDirection::ClientBound, 0)
+        .unwrap()
+    {
+        Event::ChatMessage(m) => {
+            assert_eq!(m.message.to_plain_string(), "<Notch> hello");
+            assert_eq!(m.position, ChatPosition::Chat);
+        }
+        e => panic!("Expected ChatMessage, got {:?}", e),
+    }
+}
+
+#[test]
+fn test_send_chat_message() {
+    let event = Event::SendChatMessage(SendChatMessage {
+        message: "/help".to_owned(),
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    assert_eq!(bytes, vec![0x07, 0x01, 0x05, b'/', b'h', b'e', b'l', b'p']);
+    assert_eq!(read, event);
+
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    let result = dispatcher.write_event(
+        &mut io::Cursor::new(Vec::new()),
+        Event::SendChatMessage(SendChatMessage {
+            message: "a".repeat(257),
+        }),
+        &EventState::Play,
+        &EventDirection::ServerBound,
+        0,
+    );
+    assert!(matches!(result, Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_player_digging() {
+    let event = Event::PlayerDigging(PlayerDigging {
+        status: DiggingStatus::StartDestroy,
+        location: Position {
+            x: -12,
+            y: 64,
+            z: 301,
+        },
+        face: BlockFace::Top,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    // Length, id and status, then the face after the 8 byte position.
+    assert_eq!(bytes[..3], [0x0B, 0x07, 0x00]);
+    assert_eq!(bytes[11..], [0x01]);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_player_block_placement() {
+    let event = Event::PlayerBlockPlacement(PlayerBlockPlacement {
+        location: Position { x: 5, y: 70, z: -3 },
+        face: BlockFace::North,
+        held_item: Slot {
+            item_id: Some(4),
+            item_count: 32,
+            damage: Some(0),
+            nbt: Some(nbt::Blob::new()),
+        },
+        cursor_x: 8,
+        cursor_y: 16,
+        cursor_z: 0,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    // Id, position, face, then the slot's item id as a Short.
+    assert_eq!(bytes[1], 0x08);
+    assert_eq!(bytes[10..13], [0x02, 0x00, 0x04]);
+    assert_eq!(bytes[bytes.len() - 3..], [8, 16, 0]);
+
+    match read {
+        Event::PlayerBlockPlacement(ref e) => assert_eq!(e.held_item.item_id, Some(4)),
+        _ => panic!("expected PlayerBlockPlacement, got {:?}", read),
+    }
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_block_changes() {
+    let event = Event::BlockChange(BlockChange {
+        location: Position { x: 10, y: 4, z: -7 },
+        block_state: 1 << 4 | 2,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes[1], 0x23);
+    assert_eq!(read, event);
+
+    let event = Event::MultiBlockChange(MultiBlockChange {
+        chunk_x: -2,
+        chunk_z: 5,
+        records: vec![
+            BlockChangeRecord {
+                horizontal_position: 3 << 4 | 15,
+                y_position: 64,
+                block_state: 0,
+            },
+            BlockChangeRecord {
+                horizontal_position: 0,
+                y_position: 255,
+                block_state: 35 << 4 | 14,
+            },
+        ],
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes[1], 0x22);
+    assert_eq!(read, event);
+
+    if let Event::MultiBlockChange(e) = read {
+        let record = &e.records[0];
+        assert_eq!(
+            (
+                record.relative_x(),
+                record.relative_y(),
+                record.relative_z()
+            ),
+            (3, 64, 15)
+        );
+    }
+}
+
+#[test]
+fn test_chunk_data() {
+    // Single overworld section at y = 4 (blocks 64..80), all air except a
+    // stone block (1:0) at x = 3, y = 66, z = 9. Block light is 0 and sky
+    // light is 15 everywhere.
+    let mut data = vec![0u8; 4096 * 2];
+    let index = 2 << 8 | 9 << 4 | 3;
+    data[index * 2..index * 2 + 2].copy_from_slice(&(1u16 << 4).to_le_bytes());
+    data.extend(vec![0x00; 2048]);
+    data.extend(vec![0xFF; 2048]);
+    data.extend(vec![1; 256]);
+
+    let mut fixture = vec![0x21];
+    fixture.extend(&7i32.to_be_bytes());
+    fixture.extend(&(-3i32).to_be_bytes());
+    fixture.push(0x01);
+    fixture.extend(&(1u16 << 4).to_be_bytes());
+    VarInt(data.len() as i32).write_to(&mut fixture).unwrap();
+    fixture.extend(data);
+
+    let mut packet = Vec::new();
+    VarInt(fixture.len() as i32).write_to(&mut packet).unwrap();
+    packet.extend(fixture);
+
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    let event = dispatcher
+        .read_event(
+            &mut io::Cursor::new(packet.clone()),
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        )
+        .unwrap();
+
+    let chunk: &Chunk = match &event {
+        Event::ChunkData(e) => {
+            assert_eq!((e.chunk_x, e.chunk_z), (7, -3));
+            assert!(e.ground_up_continuous);
+            &e.chunk
+        }
+        _ => panic!("expected ChunkData, got {:?}", event),
+    };
+
+    assert_eq!(chunk.primary_bit_mask().unwrap(), 1 << 4);
+    assert_eq!(chunk.get_block(3, 66, 9), Some(1 << 4));
+    assert_eq!(chunk.get_block(3, 67, 9), Some(0));
+    assert_eq!(chunk.get_block(3, 10, 9), None);
+    assert_eq!(chunk.get_block(16, 66, 9), None);
+    assert_eq!(chunk.get_block(3, 256, 9), None);
+
+    let section = chunk.sections[4].as_ref().unwrap();
+    assert_eq!(section.get_block(3, 2, 9), Some(1 << 4));
+    assert_eq!(section.get_block_light(3, 2, 9), Some(0));
+    assert_eq!(section.get_sky_light(3, 2, 9), Some(15));
+
+    // Out of range coordinates don't wrap into another block.
+    assert_eq!(section.get_block(19, 2, 8), None);
+    assert_eq!(section.get_block(3, 2, usize::MAX), None);
+    assert_eq!(section.get_block_light(0, 16, 0), None);
+    assert_eq!(section.get_sky_light(16, 0, 0), None);
+    assert_eq!(chunk.biomes.unwrap()[0], 1);
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, packet);
+    assert_eq!(read, event);
+
+    // Columns the reader would reject can't be written.
+    let original = match &event {
+        Event::ChunkData(e) => e.clone(),
+        _ => unreachable!(),
+    };
+    let malformed: Vec<fn(&mut Chunk)> = vec![
+        |c| c.sections.resize(17, None),
+        |c| c.sections[4].as_mut().unwrap().block_light.truncate(100),
+        |c| c.sections[4].as_mut().unwrap().sky_light = Some(vec![0; 10]),
+        |c| {
+            c.sections[3] = Some(ChunkSection {
+                blocks: vec![0; 4096],
+                block_light: vec![0; 2048],
+                sky_light: None,
+            })
+        },
+    ];
+    for modify in malformed {
+        let mut chunk_data = original.clone();
+        modify(&mut chunk_data.chunk);
+        assert!(dispatcher
+            .write_event(
+                &mut io::Cursor::new(Vec::new()),
+                Event::ChunkData(chunk_data),
+                &EventState::Play,
+                &EventDirection::ClientBound,
+                0,
+            )
+            .is_err());
+    }
+
+    // Bytes after the chunk data.
+    let mut trailing = packet[2..].to_vec();
+    trailing.push(0);
+    assert!(matches!(
+        dispatcher.read_event(
+            &mut io::Cursor::new(frame(0x21, &trailing[1..])),
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        ),
+        Err(Error::InvalidValue(_))
+    ));
+
+    // Data that is longer than the rest of the packet.
+    let mut body = vec![0; 9];
+    body.extend(&(1u16 << 4).to_be_bytes());
+    VarInt(100).write_to(&mut body).unwrap();
+    body.extend(&[0; 10]);
+    assert!(dispatcher
+        .read_event(
+            &mut io::Cursor::new(frame(0x21, &body)),
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        )
+        .is_err());
+}
+
+#[test]
+fn test_map_chunk_bulk() {
+    // Two overworld columns: (0, 0) with a section at y = 0 holding a stone
+    // block (1:0) at its origin, and (1, -1) with sections at y = 1 and 2
+    // that are all air. Block light is 0 and sky light is 15 everywhere.
+    let section = |block: u16| {
+        let mut data = vec![0u8; 4096 * 2];
+        data[..2].copy_from_slice(&block.to_le_bytes());
+        data
+    };
+    let columns: [(i32, i32, u16, Vec<u16>); 2] =
+        [(0, 0, 0b1, vec![1 << 4]), (1, -1, 0b110, vec![0, 0])];
+
+    let mut fixture = vec![0x26, 0x01, 0x02];
+    for (x, z, mask, _) in columns.iter() {
+        fixture.extend(&x.to_be_bytes());
+        fixture.extend(&z.to_be_bytes());
+        fixture.extend(&mask.to_be_bytes());
+    }
+    for (_, _, _, blocks) in columns.iter() {
+        for block in blocks {
+            fixture.extend(section(*block));
+        }
+        fixture.extend(vec![0x00; 2048 * blocks.len()]);
+        fixture.extend(vec![0xFF; 2048 * blocks.len()]);
+        fixture.extend(vec![1; 256]);
+    }
+
+    let mut packet = Vec::new();
+    VarInt(fixture.len() as i32).write_to(&mut packet).unwrap();
+    packet.extend(fixture);
+
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    let event = dispatcher
+        .read_event(
+            &mut io::Cursor::new(packet.clone()),
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        )
+        .unwrap();
+
+    let chunks = match &event {
+        Event::MapChunkBulk(e) => {
+            assert!(e.sky_light);
+            &e.chunks
+        }
+        _ => panic!("expected MapChunkBulk, got {:?}", event),
+    };
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!((chunks[0].chunk_x, chunks[0].chunk_z), (0, 0));
+    assert_eq!((chunks[1].chunk_x, chunks[1].chunk_z), (1, -1));
+    assert!(chunks.iter().all(|c| c.ground_up_continuous));
+
+    assert_eq!(chunks[0].chunk.get_block(0, 0, 0), Some(1 << 4));
+    assert_eq!(chunks[0].chunk.get_block(0, 16, 0), None);
+    assert_eq!(chunks[1].chunk.primary_bit_mask().unwrap(), 0b110);
+    assert_eq!(chunks[1].chunk.get_block(0, 16, 0), Some(0));
+    let section = chunks[1].chunk.sections[2].as_ref().unwrap();
+    assert_eq!(section.get_sky_light(5, 5, 5), Some(15));
+    assert_eq!(chunks[1].chunk.biomes.unwrap()[255], 1);
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, packet);
+    assert_eq!(read, event);
+
+    // Truncated column data is rejected.
+    let body_start = VarInt(packet.len() as i32).encoded_len() + 1;
+    let truncated = frame(0x26, &packet[body_start..packet.len() - 1]);
+    assert!(matches!(
+        dispatcher.read_event(
+            &mut io::Cursor::new(truncated),
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        ),
+        Err(Error::InvalidValue(_))
+    ));
+
+    // So are sections whose sky light doesn't match the flag.
+    let mismatched = Event::MapChunkBulk(MapChunkBulk {
+        sky_light: false,
+        chunks: chunks.clone(),
+    });
+    assert!(dispatcher
+        .write_event(
+            &mut io::Cursor::new(Vec::new()),
+            mismatched,
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        )
+        .is_err());
+}
+
+#[test]
+fn test_tab_complete() {
+    let requests = vec![
+        TabComplete {
+            text: "/gamemode cr".to_owned(),
+            looked_at: None,
+        },
+        TabComplete {
+            text: "/setblock ".to_owned(),
+            looked_at: Some(Position { x: 1, y: 2, z: 3 }),
+        },
+    ];
+
+    for request in requests {
+        let event = Event::TabComplete(request);
+        let (bytes, read) = roundtrip(
+            ProtocolVersion::V47,
+            event.clone(),
+            EventState::Play,
+            EventDirection::ServerBound,
+        );
+        assert_eq!(bytes[1], 0x14);
+        assert_eq!(read, event);
+    }
+
+    let event = Event::TabCompleteResponse(TabCompleteResponse {
+        matches: vec!["/help".to_owned(), "/home".to_owned()],
+    });
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes[1..4], [0x3A, 0x02, 0x05]);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_update_health() {
+    let event = Event::UpdateHealth(UpdateHealth {
+        health: 13.5,
+        food: 17,
+        saturation: 2.25,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    // Id, two floats and a single byte VarInt.
+    assert_eq!(bytes[..2], [0x0A, 0x06]);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_set_experience() {
+    let event = Event::SetExperience(SetExperience {
+        experience_bar: 0.75,
+        level: 30,
+        total_experience: 1395,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    // Id, float, then a one and a two byte VarInt.
+    assert_eq!(
+        bytes,
+        vec![0x08, 0x1F, 0x3F, 0x40, 0x00, 0x00, 0x1E, 0xF3, 0x0A]
+    );
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_named_sound_effect() {
+    let event = Event::NamedSoundEffect(NamedSoundEffect {
+        sound: "random.explode".to_owned(),
+        x: 100.5,
+        y: 64.0,
+        z: -20.125,
+        volume: 4.0,
+        pitch: 63,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    // Positions are multiplied by 8.
+    assert_eq!(
+        bytes[17..29],
+        [0, 0, 0x03, 0x24, 0, 0, 0x02, 0, 0xFF, 0xFF, 0xFF, 0x5F]
+    );
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_titles() {
+    let text = Chat {
+        text: Some("Welcome".to_owned()),
+        ..Default::default()
+    };
+    let actions = [
+        (TitleAction::SetTitle(text.clone()), 0x00),
+        (TitleAction::SetSubtitle(text.clone()), 0x01),
+        (TitleAction::SetActionBar(text), 0x02),
+        (
+            TitleAction::SetTimes {
+                fade_in: 10,
+                stay: 70,
+                fade_out: 20,
+            },
+            0x03,
+        ),
+        (TitleAction::Hide, 0x04),
+        (TitleAction::Reset, 0x05),
+    ];
+
+    for (action, id) in actions.iter() {
+        let event = Event::Title(Title {
+            action: action.clone(),
+        });
+        let (bytes, read) = roundtrip(
+            ProtocolVersion::V754,
+            event.clone(),
+            EventState::Play,
+            EventDirection::ClientBound,
+        );
+        // All of them are the same packet, with different actions.
+        let mut body = &bytes[..];
+        VarInt::read_from(&mut body).unwrap();
+        assert_eq!(body[..2], [0x4F, *id]);
+        assert_eq!(read, event);
+    }
+
+    // Unknown actions are rejected.
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V754);
+    let mut buf = io::Cursor::new(frame(0x4F, &[0x06]));
+    assert!(matches!(
+        dispatcher.read_event(&mut buf, &EventState::Play, &EventDirection::ClientBound, 0),
+        Err(Error::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn test_boss_bar() {
+    let uuid = uuid::Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5);
+    let add = Event::BossBar(BossBar {
+        uuid,
+        action: BossBarAction::Add {
+            title: Chat {
+                text: Some("Ender Dragon".to_owned()),
+                ..Default::default()
+            },
+            health: 0.5,
+            color: 5,
+            division: 0,
+            flags: 0x03,
+        },
+    });
+
+    let (_, read) = roundtrip(
+        ProtocolVersion::V754,
+        add.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(read, add);
+
+    let remove = Event::BossBar(BossBar {
+        uuid,
+        action: BossBarAction::Remove,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V754,
+        remove.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    let mut body = vec![0x0C];
+    body.extend_from_slice(uuid.as_bytes());
+    body.push(0x01);
+    assert_eq!(bytes[1..], body[..]);
+    assert_eq!(read, remove);
+}
+
+#[test]
+fn test_client_settings() {
+    let event = Event::ClientSettings(ClientSettings {
+        view_distance: 12,
+        main_hand: 0,
+        ..Default::default()
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V754,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    let mut body = vec![0x05];
+    "en_US".to_owned().write_to(&mut body).unwrap();
+    body.extend_from_slice(&[12, 0x00, 0x01, 0x7F, 0x00]);
+    assert_eq!(bytes[1..], body[..]);
+    assert_eq!(read, event);
+
+    // 1.8 has no main hand, which is always read as right.
+    let event = Event::ClientSettings(ClientSettings::default());
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    let mut body = vec![0x15];
+    "en_US".to_owned().write_to(&mut body).unwrap();
+    body.extend_from_slice(&[8, 0x00, 0x01, 0x7F]);
+    assert_eq!(bytes[1..], body[..]);
+    assert_eq!(read, event);
+
+    // The 1.8 chat mode is a single byte.
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    assert!(dispatcher
+        .write_event(
+            &mut io::Cursor::new(Vec::new()),
+            Event::ClientSettings(ClientSettings {
+                chat_mode: 300,
+                ..Default::default()
+            }),
+            &EventState::Play,
+            &EventDirection::ServerBound,
+            0,
+        )
+        .is_err());
+}
+
+#[test]
+fn test_animations() {
+    let swing = Event::Animation(Animation {
+        hand: Hand::MainHand,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        swing.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    assert_eq!(bytes, vec![0x01, 0x0A]);
+    assert_eq!(read, swing);
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V754,
+        swing.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    assert_eq!(bytes, vec![0x02, 0x2C, 0x00]);
+    assert_eq!(read, swing);
+
+    let entity_swing = Event::EntityAnimation(EntityAnimation {
+        entity_id: 300,
+        animation: AnimationType::SwingMainArm,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        entity_swing.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, vec![0x04, 0x0B, 0xAC, 0x02, 0x00]);
+    assert_eq!(read, entity_swing);
+}
+
+#[test]
+fn test_interact_entity() {
+    let attack = Event::InteractEntity(InteractEntity {
+        entity_id: 42,
+        interaction: EntityInteraction::Attack,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        attack.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    assert_eq!(bytes, vec![0x03, 0x02, 0x2A, 0x01]);
+    assert_eq!(read, attack);
+
+    let interact_at = Event::InteractEntity(InteractEntity {
+        entity_id: 42,
+        interaction: EntityInteraction::InteractAt {
+            x: 0.5,
+            y: 1.25,
+            z: -0.5,
+        },
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        interact_at.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    assert_eq!(bytes[..4], [0x0F, 0x02, 0x2A, 0x02]);
+    assert_eq!(read, interact_at);
+}
+
+#[test]
+fn test_set_held_item() {
+    let event = Event::SetHeldItem(SetHeldItem { slot: 4 });
+
+    for (version, id) in [(ProtocolVersion::V47, 0x09), (ProtocolVersion::V754, 0x25)].iter() {
+        let (bytes, read) = roundtrip(
+            *version,
+            event.clone(),
+            EventState::Play,
+            EventDirection::ServerBound,
+        );
+        assert_eq!(bytes, vec![0x03, *id, 0x00, 0x04]);
+        assert_eq!(read, event);
+    }
+}
+
+#[test]
+fn test_entity_equipment() {
+    let event = Event::EntityEquipment(EntityEquipment {
+        entity_id: 12,
+        slot: EquipmentSlot::Helmet,
+        item: Slot {
+            item_id: Some(310),
+            item_count: 1,
+            damage: Some(5),
+            nbt: None,
+        },
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    // Id, entity id, slot, item id, count, damage and an end tag.
+    assert_eq!(
+        bytes,
+        vec![0x0A, 0x04, 0x0C, 0x00, 0x04, 0x01, 0x36, 0x01, 0x00, 0x05, 0x00]
+    );
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_spectating() {
+    let camera = Event::Camera(Camera { camera_id: 300 });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        camera.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, vec![0x03, 0x43, 0xAC, 0x02]);
+    assert_eq!(read, camera);
+
+    let uuid = uuid::Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5);
+    let spectate = Event::Spectate(Spectate { target: uuid });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        spectate.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    assert_eq!(bytes[..2], [0x11, 0x18]);
+    assert_eq!(bytes[2..], uuid.as_bytes()[..]);
+    assert_eq!(read, spectate);
+}
+
+#[test]
+fn test_collect_item() {
+    let event = Event::CollectItem(CollectItem {
+        collected_entity_id: 300,
+        collector_entity_id: 1,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, vec![0x04, 0x0D, 0xAC, 0x02, 0x01]);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_entity_status() {
+    let status = EntityStatus {
+        entity_id: 300,
+        status: 3,
+    };
+    assert_eq!(status.status_kind(), Some(EntityStatusKind::Death));
+
+    let event = Event::EntityStatus(status);
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, vec![0x06, 0x1A, 0x00, 0x00, 0x01, 0x2C, 0x03]);
+    assert_eq!(read, event);
+
+    let unknown = EntityStatus {
+        entity_id: 1,
+        status: 200,
+    };
+    assert_eq!(unknown.status_kind(), None);
+}
+
+#[test]
+fn test_attach_entity() {
+    let attach = |holding_entity_id, leash| {
+        Event::AttachEntity(AttachEntity {
+            attached_entity_id: 10,
+            holding_entity_id,
+            leash,
+        })
+    };
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        attach(20, true),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(
+        bytes,
+        vec![0x0A, 0x1B, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x14, 0x01]
+    );
+    assert_eq!(read, attach(20, true));
+
+    // Detaching from a vehicle.
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        attach(-1, false),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(&bytes[6..], &[0xFF, 0xFF, 0xFF, 0xFF, 0x00]);
+    assert_eq!(read, attach(-1, false));
+}
+
+#[test]
+fn test_explosion() {
+    let event = Event::Explosion(Explosion {
+        x: 10.5,
+        y: 64.0,
+        z: -3.25,
+        radius: 4.0,
+        records: vec![(1, 0, -1), (-2, 3, 0)],
+        player_motion: (0.5, 0.25, -0.125),
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes[1], 0x27);
+    assert_eq!(
+        &bytes[18..28],
+        &[0, 0, 0, 2, 0x01, 0x00, 0xFF, 0xFE, 0x03, 0x00]
+    );
+    assert_eq!(read, event);
+
+    // A negative record count is rejected.
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    let mut body = vec![0; 16];
+    body.extend_from_slice(&[0xFF; 4]);
+    let mut buf = io::Cursor::new(frame(0x27, &body));
+    assert!(matches!(
+        dispatcher.read_event(&mut buf, &EventState::Play, &EventDirection::ClientBound, 0),
+        Err(Error::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn test_client_status() {
+    for (action, byte) in [
+        (ClientStatusAction::PerformRespawn, 0x00),
+        (ClientStatusAction::RequestStats, 0x01),
+    ]
+    .iter()
+    {
+        let event = Event::ClientStatus(ClientStatus { action: *action });
+
+        let (bytes, read) = roundtrip(
+            ProtocolVersion::V47,
+            event.clone(),
+            EventState::Play,
+            EventDirection::ServerBound,
+        );
+        assert_eq!(bytes, vec![0x02, 0x16, *byte]);
+        assert_eq!(read, event);
+    }
+}
+
+#[test]
+fn test_respawn() {
+    let event = Event::Respawn(Respawn {
+        dimension: Dimension::Nether,
+        difficulty: Some(Difficulty::Hard),
+        gamemode: Gamemode::Survival,
+        level_type: Some("default".to_owned()),
+        dimension_codec: None,
+        world_name: None,
+        hashed_seed: None,
+        previous_gamemode: None,
+        is_debug: None,
+        is_flat: None,
+        copy_metadata: None,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes[1..6], [0x07, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(read, event);
+
+    let mut dimension_type = nbt::Blob::new();
+    dimension_type
+        .insert("effects", "minecraft:the_end")
+        .unwrap();
+    dimension_type.insert("has_skylight", 0i8).unwrap();
+
+    let event = Event::Respawn(Respawn {
+        dimension: Dimension::End,
+        difficulty: None,
+        gamemode: Gamemode::Creative,
+        level_type: None,
+        dimension_codec: Some(dimension_type),
+        world_name: Some("minecraft:the_end".to_owned()),
+        hashed_seed: Some(-4_000_000_000),
+        previous_gamemode: Some(Gamemode::Survival),
+        is_debug: Some(false),
+        is_flat: Some(true),
+        copy_metadata: Some(true),
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V754,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes[1], 0x39);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_player_movement() {
+    let events = vec![
+        (
+            Event::PlayerPosition(PlayerPosition {
+                x: 10.5,
+                feet_y: 64.0,
+                z: -20.25,
+                on_ground: true,
+            }),
+            0x04,
+        ),
+        (
+            Event::PlayerLook(PlayerLook {
+                yaw: 270.0,
+                pitch: -45.5,
+                on_ground: false,
+            }),
+            0x05,
+        ),
+        (
+            Event::PlayerPositionAndLookServerbound(PlayerPositionAndLookServerbound {
+                x: 0.0,
+                feet_y: 70.125,
+                z: 1e6,
+                yaw: 0.0,
+                pitch: 90.0,
+                on_ground: true,
+            }),
+            0x06,
+        ),
+    ];
+
+    for (event, id) in events {
+        let (bytes, read) = roundtrip(
+            ProtocolVersion::V47,
+            event.clone(),
+            EventState::Play,
+            EventDirection::ServerBound,
+        );
+        assert_eq!(bytes[1], id);
+        assert_eq!(read, event);
+    }
+}
+
+#[test]
+fn test_teleport_confirm() {
+    let event = Event::TeleportConfirm(TeleportConfirm { teleport_id: 300 });
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V754,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    assert_eq!(bytes, vec![0x03, 0x00, 0xAC, 0x02]);
+    assert_eq!(read, event);
+
+    let event = Event::PlayerPositionAndLook(PlayerPositionAndLook {
+        x: RelativeOrAbsolute::Absolute(8.5),
+        y: RelativeOrAbsolute::Absolute(65.0),
+        z: RelativeOrAbsolute::Relative(0.0),
+        yaw: RelativeOrAbsolute::Relative(0.0),
+        pitch: RelativeOrAbsolute::Absolute(10.0),
+        teleport_id: Some(7),
+    });
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V754,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes[1], 0x34);
+    assert_eq!(*bytes.last().unwrap(), 7);
+    match &read {
+        Event::PlayerPositionAndLook(p) => assert_eq!(p.teleport_id, Some(7)),
+        e => panic!("Expected PlayerPositionAndLook, got {:?}", e),
+    }
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_window_packets() {
+    let event = Event::ClickWindow(ClickWindow {
+        window_id: 1,
+        slot: 36,
+        button: 0,
+        action_number: 12,
+        mode: 0,
+        clicked_item: Slot {
+            item_id: Some(276),
+            item_count: 1,
+            damage: Some(0),
+            nbt: Some(nbt::Blob::new()),
+        },
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ServerBound,
+    );
+    // Id, window, slot, button, action number and mode.
+    assert_eq!(
+        bytes[1..10],
+        [0x0E, 0x01, 0x00, 0x24, 0x00, 0x00, 0x0C, 0x00, 0x01]
+    );
+    assert_eq!(read, event);
+
+    let event = Event::CloseWindow(CloseWindow { window_id: 1 });
+    for (direction, id) in [
+        (EventDirection::ServerBound, 0x0D),
+        (EventDirection::ClientBound, 0x2E),
+    ]
+    .iter()
+    {
+        let (bytes, read) = roundtrip(
+            ProtocolVersion::V47,
+            event.clone(),
+            EventState::Play,
+            *direction,
+        );
+        assert_eq!(bytes, vec![0x02, *id, 0x01]);
+        assert_eq!(read, event);
+    }
+}
+
+#[test]
+fn test_play_disconnect() {
+    let event = Event::Disconnect(Disconnect {
+        reason: Chat {
+            text: Some("Kicked".to_owned()),
+            ..Default::default()
+        },
+    });
+
+    for (version, id) in [(ProtocolVersion::V47, 0x40), (ProtocolVersion::V754, 0x19)].iter() {
+        let (bytes, read) = roundtrip(
+            *version,
+            event.clone(),
+            EventState::Play,
+            EventDirection::ClientBound,
+        );
+        // The serialized reason takes the length past one byte.
+        assert_eq!(bytes[2], *id);
+        assert_eq!(read, event);
+    }
+}
+
+#[test]
+fn test_window_property() {
+    // Furnace fuel left, in ticks.
+    let event = Event::WindowProperty(WindowProperty {
+        window_id: 4,
+        property: 0,
+        value: 1600,
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, vec![0x06, 0x31, 0x04, 0x00, 0x00, 0x06, 0x40]);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_confirm_transaction() {
+    for (accepted, direction, id) in [
+        (true, EventDirection::ClientBound, 0x32),
+        (false, EventDirection::ClientBound, 0x32),
+        (false, EventDirection::ServerBound, 0x0F),
+    ]
+    .iter()
+    {
+        let event = Event::ConfirmTransaction(ConfirmTransaction {
+            window_id: 1,
+            action_number: 12,
+            accepted: *accepted,
+        });
+
+        let (bytes, read) = roundtrip(
+            ProtocolVersion::V47,
+            event.clone(),
+            EventState::Play,
+            *direction,
+        );
+        assert_eq!(bytes, vec![0x05, *id, 0x01, 0x00, 0x0C, *accepted as u8]);
+        assert_eq!(read, event);
+    }
+}
+
+#[test]
+fn test_slot_without_nbt() {
+    let event = Event::SlotUpdate(SlotUpdate {
+        window_id: 0,
+        slot: 36,
+        data: Slot {
+            item_id: Some(1),
+            item_count: 1,
+            damage: None,
+            nbt: None,
+        },
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event,
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    // Item id, count, damage and an end tag.
+    assert_eq!(bytes[5..], [0x00, 0x01, 0x01, 0x00, 0x00, 0x00]);
+    assert_eq!(
+        read,
+        Event::SlotUpdate(SlotUpdate {
+            window_id: 0,
+            slot: 36,
+            data: Slot {
+                item_id: Some(1),
+                item_count: 1,
+                damage: Some(0),
+                nbt: None,
+            },
+        })
+    );
+}
+
+#[test]
+fn test_open_window() {
+    let chest = Event::OpenWindow(OpenWindow {
+        window_id: 2,
+        window_type: "minecraft:chest".to_owned(),
+        title: Chat {
+            text: Some("Chest".to_owned()),
+            ..Default::default()
+        },
+        slot_count: 27,
+        entity_id: None,
+    });
+    let horse = Event::OpenWindow(OpenWindow {
+        window_id: 3,
+        window_type: "EntityHorse".to_owned(),
+        title: Chat {
+            text: Some("Horse".to_owned()),
+            ..Default::default()
+        },
+        slot_count: 2,
+        entity_id: Some(1234),
+    });
+
+    let (chest_bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        chest.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    // The slot count is the last field when there's no entity id.
+    assert_eq!(chest_bytes.last(), Some(&27));
+    assert_eq!(read, chest);
+
+    let (horse_bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        horse.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(
+        horse_bytes[horse_bytes.len() - 4..],
+        [0x00, 0x00, 0x04, 0xD2]
+    );
+    assert_eq!(read, horse);
+
+    // Entity ids that don't match the window type are rejected.
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    for (window_type, entity_id) in [("minecraft:chest", Some(1234)), ("EntityHorse", None)] {
+        let window = Event::OpenWindow(OpenWindow {
+            window_id: 4,
+            window_type: window_type.to_owned(),
+            title: Chat::default(),
+            slot_count: 2,
+            entity_id,
+        });
+        assert!(matches!(
+            dispatcher.write_event(
+                &mut io::Cursor::new(Vec::new()),
+                window,
+                &EventState::Play,
+                &EventDirection::ClientBound,
+                0,
+            ),
+            Err(Error::InvalidValue(_))
+        ));
+    }
+}
+
+#[test]
+fn test_entity_movement() {
+    let moves = vec![
+        Event::EntityRelativeMove(EntityRelativeMove {
+            entity_id: 300,
+            delta_x: 1.5,
+            delta_y: -0.25,
+            delta_z: -3.96875,
+            on_ground: true,
+        }),
+        Event::EntityLookAndRelativeMove(EntityLookAndRelativeMove {
+            entity_id: 1,
+            delta_x: -1.0,
+            delta_y: 0.0,
+            delta_z: 0.03125,
+            yaw: Angle(64),
+            pitch: Angle(192),
+            on_ground: false,
+        }),
+        Event::EntityTeleport(EntityTeleport {
+            entity_id: 2,
+            x: -1024.5,
+            y: 64.0,
+            z: 8000.03125,
+            yaw: Angle(128),
+            pitch: Angle(0),
+            on_ground: true,
+        }),
+    ];
+
+    for event in moves {
+        let (_, read) = roundtrip(
+            ProtocolVersion::V47,
+            event.clone(),
+            EventState::Play,
+            EventDirection::ClientBound,
+        );
+        assert_eq!(read, event);
+    }
+
+    let (bytes, _) = roundtrip(
+        ProtocolVersion::V47,
+        Event::EntityRelativeMove(EntityRelativeMove {
+            entity_id: 5,
+            delta_x: -1.0,
+            delta_y: 0.5,
+            delta_z: 0.0,
+            on_ground: true,
+        }),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, frame(0x15, &[0x05, 0xe0, 0x10, 0x00, 0x01]));
+
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    let result = dispatcher.write_event(
+        &mut io::Cursor::new(Vec::new()),
+        Event::EntityRelativeMove(EntityRelativeMove {
+            entity_id: 5,
+            delta_x: 4.0,
+            delta_y: 0.0,
+            delta_z: 0.0,
+            on_ground: true,
+        }),
+        &EventState::Play,
+        &EventDirection::ClientBound,
+        0,
+    );
+    assert!(matches!(result, Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_entity_velocity() {
+    let velocity = EntityVelocity {
+        entity_id: 42,
+        velocity_x: -4000,
+        velocity_y: 800,
+        velocity_z: -8000,
+    };
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        Event::EntityVelocity(velocity.clone()),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(
+        bytes,
+        frame(0x12, &[0x2A, 0xF0, 0x60, 0x03, 0x20, 0xE0, 0xC0])
+    );
+    assert_eq!(read, Event::EntityVelocity(velocity.clone()));
+    assert_eq!(velocity.to_blocks_per_tick(), (-0.5, 0.1, -1.0));
+}
+
+#[test]
+fn test_entity_head_look() {
+    let event = Event::EntityHeadLook(EntityHeadLook {
+        entity_id: 7,
+        head_yaw: Angle::from_degrees(90.0),
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes, frame(0x19, &[0x07, 0x40]));
+    assert_eq!(read, event);
+
+    match read {
+        Event::EntityHeadLook(e) => assert_eq!(e.head_yaw.to_degrees(), 90.0),
+        e => panic!("Expected EntityHeadLook, got {:?}", e),
+    }
+}
+
+#[test]
+fn test_spawn_mob() {
+    let event = Event::SpawnMob(SpawnMob {
+        entity_id: 81,
+        uuid: None,
+        // Zombie
+        entity_type: 54,
+        x: 12.5,
+        y: 64.0,
+        z: -3.25,
+        yaw: Angle(64),
+        pitch: Angle(0),
+        head_pitch: Angle(200),
+        velocity: (400, -1200, 0),
+        metadata: EntityMetadata(vec![MetadataEntry {
+            index: 0,
+            value: MetadataValue::Byte(0),
+        }]),
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        event.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(bytes[1..4], [0x0F, 81, 54]);
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_spawn_and_destroy() {
+    let spawn = Event::SpawnPlayer(SpawnPlayer {
+        entity_id: 42,
+        uuid: uuid::Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5),
+        x: 100.5,
+        y: 64.0,
+        z: -20.25,
+        yaw: Angle::from_degrees(90.0),
+        pitch: Angle::from_degrees(-45.0),
+        current_item: 276,
+        metadata: EntityMetadata(vec![MetadataEntry {
+            index: 0,
+            value: MetadataValue::Byte(0x02),
+        }]),
+    });
+    let (_, read) = roundtrip(
+        ProtocolVersion::V47,
+        spawn.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(read, spawn);
+
+    let destroy = Event::DestroyEntities(DestroyEntities {
+        entity_ids: vec![1, 300, 70000],
+    });
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V47,
+        destroy.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+    assert_eq!(
+        bytes,
+        frame(0x13, &[0x03, 0x01, 0xac, 0x02, 0xf0, 0xa2, 0x04])
+    );
+    assert_eq!(read, destroy);
+}
+
+#[test]
+fn test_v340_join_game() {
+    let join = Event::JoinGame(JoinGame {
+        id: 7,
+        is_hardcore: true,
+        gamemode: Gamemode::Adventure,
+        dimension: Some(Dimension::Nether),
+        difficulty: Some(Difficulty::Hard),
+        max_players: 20,
+        level_type: Some("default".to_owned()),
+        reduced_debug: false,
+        ..Default::default()
+    });
+
+    let (bytes, read) = roundtrip(
+        ProtocolVersion::V340,
+        join.clone(),
+        EventState::Play,
+        EventDirection::ClientBound,
+    );
+
+    let mut body = vec![0, 0, 0, 7, 0x0A, 0xFF, 0xFF, 0xFF, 0xFF, 3, 20, 7];
+    body.extend_from_slice(b"default");
+    body.push(0);
+    assert_eq!(bytes, frame(0x23, &body));
+    assert_eq!(read, join);
+}
+
+/// Handshake and login start sent by a 1.15.2 client.
+#[rustfmt::skip]
+const V578_LOGIN_SERVER_BOUND: &[u8] = &[
+    // Handshake
+    0x10, 0x00, 0xc2, 0x04, 0x09, b'l', b'o', b'c', b'a', b'l', b'h', b'o', b's', b't',
+    0x63, 0xdd, 0x02,
+    // LoginStart
+    0x07, 0x00, 0x05, b't', b'e', b't', b's', b'u',
+];
+
+/// Login success and join game sent by a 1.15.2 server.
+const V578_LOGIN_CLIENT_BOUND: &[u8] = &[
+    // LoginSuccess
+    0x2c, 0x02, 0x24, b'0', b'6', b'9', b'a', b'7', b'9', b'f', b'4', b'-', b'4', b'4', b'e', b'9',
+    b'-', b'4', b'7', b'2', b'6', b'-', b'a', b'5', b'b', b'e', b'-', b'f', b'c', b'a', b'9', b'0',
+    b'e', b'3', b'8', b'a', b'a', b'f', b'5', 0x05, b't', b'e', b't', b's', b'u',
+    // JoinGame
+    0x1e, 0x26, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05,
+    0x06, 0x07, 0x08, 0x14, 0x07, b'd', b'e', b'f', b'a', b'u', b'l', b't', 0x0a, 0x00, 0x01,
+];
+
+#[test]
+fn test_v578_login() {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V578);
+
+    let server_bound = [
+        (
+            Event::Handshake(Handshake {
+                protocol_version: None,
+                server_address: "localhost".to_owned(),
+                server_port: 25565,
+                next_state: EventState::Login,
+            }),
+            EventState::Handshake,
+        ),
+        (
+            Event::LoginStart(LoginStart {
+                name: "tetsu".to_owned(),
+            }),
+            EventState::Login,
+        ),
+    ];
+    let client_bound = [
+        (
+            Event::LoginSuccess(LoginSuccess {
+                uuid: uuid::Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5),
+                name: "tetsu".to_owned(),
+            }),
+            EventState::Login,
+        ),
+        (
+            Event::JoinGame(JoinGame {
+                id: 1,
+                is_hardcore: false,
+                gamemode: Gamemode::Creative,
+                dimension: Some(Dimension::Overworld),
+                hashed_seed: Some(0x0102030405060708),
+                max_players: 20,
+                level_type: Some("default".to_owned()),
+                view_distance: Some(10),
+                reduced_debug: false,
+                enable_respawn: Some(true),
+                ..Default::default()
+            }),
+            EventState::Play,
+        ),
+    ];
+
+    for (events, direction, fixture) in [
+        (
+            &server_bound,
+            EventDirection::ServerBound,
+            V578_LOGIN_SERVER_BOUND,
+        ),
+        (
+            &client_bound,
+            EventDirection::ClientBound,
+            V578_LOGIN_CLIENT_BOUND,
+        ),
+    ]
+    .iter()
+    {
+        let mut buf = io::Cursor::new(Vec::new());
+        for (event, state) in events.iter() {
+            dispatcher
+                .write_event(&mut buf, event.clone(), state, direction, 0)
+                .unwrap();
+        }
+        assert_eq!(&buf.get_ref()[..], *fixture);
+
+        let mut buf = io::Cursor::new(fixture.to_vec());
+        for (event, state) in events.iter() {
+            let read = dispatcher
+                .read_event(&mut buf, state, direction, 0)
+                .unwrap();
+            match (event, read) {
+                // The protocol version is filled in when writing.
+                (Event::Handshake(_), Event::Handshake(h)) => {
+                    assert_eq!(h.protocol_version, Some(578));
+                    assert_eq!(h.server_address, "localhost");
+                }
+                (event, read) => assert_eq!(&read, event),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_unknown_packet() {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+
+    // Two unregistered packets back to back.
+    let mut raw = frame(0x7a, &[0xde, 0xad, 0xbe, 0xef]);
+    raw.extend(frame(0x7b, &[]));
+    let mut buf = io::Cursor::new(raw.clone());
+
+    let read = dispatcher
+        .read_event(&mut buf, &EventState::Play, &EventDirection::ClientBound, 0)
+        .unwrap();
+    assert_eq!(
+        read,
+        Event::Unknown {
+            id: 0x7a,
+            state: EventState::Play,
+            direction: EventDirection::ClientBound,
+            data: vec![0xde, 0xad, 0xbe, 0xef],
+        }
+    );
+
+    // The whole packet was consumed.
+    let next = dispatcher
+        .read_event(&mut buf, &EventState::Play, &EventDirection::ClientBound, 0)
+        .unwrap();
+    assert!(matches!(next, Event::Unknown { id: 0x7b, ref data, .. } if data.is_empty()));
+
+    // Unknown events are written back as is.
+    let mut out = io::Cursor::new(Vec::new());
+    dispatcher
+        .write_event(
+            &mut out,
+            read,
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        )
+        .unwrap();
+    dispatcher
+        .write_event(
+            &mut out,
+            next,
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        )
+        .unwrap();
+    assert_eq!(out.into_inner(), raw);
+}
+
+#[test]
+fn test_compressed_roundtrip() {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+
+    let event = Event::SendChatMessage(SendChatMessage {
+        message: "tetsu ".repeat(40),
+    });
+
+    let mut buf = io::Cursor::new(Vec::new());
+    dispatcher
+        .write_event(
+            &mut buf,
+            event.clone(),
+            &EventState::Play,
+            &EventDirection::ServerBound,
+            64,
+        )
+        .unwrap();
+
+    // Repetitive payloads compress well below their original size.
+    assert!(buf.get_ref().len() < 100);
+
+    buf.set_position(0);
+    let read = dispatcher
+        .read_event(
+            &mut buf,
+            &EventState::Play,
+            &EventDirection::ServerBound,
+            64,
+        )
+        .unwrap();
+    assert_eq!(read, event);
+    assert_eq!(buf.position() as usize, buf.get_ref().len());
+}
+
+#[test]
+fn test_compression_level() {
+    let mut dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+    dispatcher.set_compression_level(0);
+    assert_eq!(dispatcher.get_compression_level(), 0);
+
+    let event = Event::SendChatMessage(SendChatMessage {
+        message: "tetsu ".repeat(40),
+    });
+
+    let mut buf = io::Cursor::new(Vec::new());
+    dispatcher
+        .write_event(
+            &mut buf,
+            event.clone(),
+            &EventState::Play,
+            &EventDirection::ServerBound,
+            64,
+        )
+        .unwrap();
+
+    // Stored zlib blocks are larger than the payload itself.
+    assert!(buf.get_ref().len() > 240);
+
+    buf.set_position(0);
+    let read = dispatcher
+        .read_event(
+            &mut buf,
+            &EventState::Play,
+            &EventDirection::ServerBound,
+            64,
+        )
+        .unwrap();
+    assert_eq!(read, event);
+}
+
+#[test]
+fn test_compression_threshold() {
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V47);
+
+    let small = Event::SendChatMessage(SendChatMessage {
+        message: "hi".to_owned(),
+    });
+    let large = Event::SendChatMessage(SendChatMessage {
+        message: "a".repeat(200),
+    });
+
+    let mut buf = io::Cursor::new(Vec::new());
+    for event in [small.clone(), large.clone()].iter() {
+        dispatcher
+            .write_event(
+                &mut buf,
+                event.clone(),
+                &EventState::Play,
+                &EventDirection::ServerBound,
+                64,
+            )
+            .unwrap();
+    }
+
+    // Total length, data length of 0, then the raw packet.
+    let small_len = 6;
+    assert_eq!(
+        &buf.get_ref()[..small_len],
+        &[0x05, 0x00, 0x01, 0x02, b'h', b'i']
+    );
+
+    // Data length is the uncompressed size of the large packet.
+    let mut rest = &buf.get_ref()[small_len..];
+    let _ = VarInt::read_from(&mut rest).unwrap();
+    assert_eq!(VarInt::read_from(&mut rest).unwrap().0, 1 + 2 + 200);
+
+    buf.set_position(0);
+    for event in [small, large].iter() {
+        let read = dispatcher
+            .read_event(
+                &mut buf,
+                &EventState::Play,
+                &EventDirection::ServerBound,
+                64,
+            )
+            .unwrap();
+        assert_eq!(&read, event);
+    }
+}
+
+#[test]
+fn test_v754_change_game_state() {
+    // Length, packet id, reason, then a float value that is always present.
+    let cases = vec![
+        (ChangeGameState::NoRespawnBlock, "061d0000000000"),
+        (ChangeGameState::BeginRaining, "061d0200000000"),
+        (
+            ChangeGameState::GamemodeUpdate(Gamemode::Creative),
+            "061d033f800000",
+        ),
+        (
+            ChangeGameState::WinGame(AfterGameWin::CreditsAndRespawn),
+            "061d043f800000",
+        ),
+        (
+            ChangeGameState::DemoEvent(DemoEventAction::ShowJumpControl),
+            "061d0542cc0000",
+        ),
+        (ChangeGameState::ArrowHitPlayer, "061d0600000000"),
+        (ChangeGameState::RainLevel(0.5), "061d073f000000"),
+        (ChangeGameState::ThunderLevel(1.0), "061d083f800000"),
+        (ChangeGameState::PufferfishSting, "061d0900000000"),
+        (ChangeGameState::ElderGuardianAppear, "061d0a00000000"),
+        (ChangeGameState::EnableRespawn(true), "061d0b00000000"),
+        (ChangeGameState::EnableRespawn(false), "061d0b3f800000"),
+    ];
+
+    for (state, expected) in cases {
+        roundtrip_event(
+            ProtocolVersion::V754,
+            Event::ChangeGameState(state),
+            EventState::Play,
+            EventDirection::ClientBound,
+            Some(expected),
+        );
+    }
+
+    // Reasons that only exist in older versions can't be written.
+    let dispatcher: EventDispatcher<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>> =
+        EventDispatcher::new(&ProtocolVersion::V754);
+    assert!(dispatcher
+        .write_event(
+            &mut io::Cursor::new(Vec::new()),
+            Event::ChangeGameState(ChangeGameState::MobAppear),
+            &EventState::Play,
+            &EventDirection::ClientBound,
+            0,
+        )
+        .is_err());
+
+    // Unknown reasons are rejected.
+    let mut buf = io::Cursor::new(frame(0x1D, &[0x0c, 0x00, 0x00, 0x00, 0x00]));
+    assert!(dispatcher
+        .read_event(&mut buf, &EventState::Play, &EventDirection::ClientBound, 0)
+        .is_err());
+}
@@ -0,0 +1,468 @@
+use std::io::Cursor;
+
+use crate::event::*;
+use crate::serialization::*;
+use crate::versions::common::*;
+
+#[test]
+fn test_varlong_roundtrip() {
+    let values = [
+        0,
+        1,
+        -1,
+        i64::MAX,
+        i64::MIN,
+        u32::MAX as i64 + 1,
+        0x7FFF_FFFF_FFFF,
+        -(u32::MAX as i64) * 3,
+    ];
+
+    for v in values.iter() {
+        let mut buf = Cursor::new(Vec::new());
+        VarLong(*v).write_to(&mut buf).unwrap();
+        assert!(buf.get_ref().len() <= 10);
+
+        buf.set_position(0);
+        assert_eq!(VarLong::read_from(&mut buf).unwrap().0, *v);
+    }
+}
+
+#[test]
+fn test_varint_max_len() {
+    for v in [0, -1, i32::MAX, i32::MIN].iter() {
+        let mut buf = Cursor::new(Vec::new());
+        VarInt(*v).write_to(&mut buf).unwrap();
+        assert!(buf.get_ref().len() <= 5);
+
+        buf.set_position(0);
+        assert_eq!(VarInt::read_from(&mut buf).unwrap().0, *v);
+    }
+
+    // 6 continuation bytes are never a valid VarInt.
+    let mut buf = Cursor::new(vec![0xFF; 6]);
+    assert!(VarInt::read_from(&mut buf).is_err());
+}
+
+#[test]
+fn test_encoded_len() {
+    let varints = [
+        (0, 1),
+        (127, 1),
+        (128, 2),
+        (16383, 2),
+        (16384, 3),
+        (2097151, 3),
+        (2097152, 4),
+        (i32::MAX, 5),
+        (-1, 5),
+        (i32::MIN, 5),
+    ];
+    for (v, len) in varints.iter() {
+        let mut buf = Vec::new();
+        VarInt(*v).write_to(&mut buf).unwrap();
+        assert_eq!(VarInt(*v).encoded_len(), *len);
+        assert_eq!(buf.len(), *len);
+    }
+
+    let varlongs = [(0, 1), (127, 1), (128, 2), (i64::MAX, 9), (-1, 10)];
+    for (v, len) in varlongs.iter() {
+        let mut buf = Vec::new();
+        VarLong(*v).write_to(&mut buf).unwrap();
+        assert_eq!(VarLong(*v).encoded_len(), *len);
+        assert_eq!(buf.len(), *len);
+    }
+}
+
+#[test]
+fn test_vec_helpers() {
+    let values = vec![VarInt(1), VarInt(300), VarInt(-1)];
+
+    let mut buf = Vec::new();
+    write_vec::<Short, _, _>(&mut buf, &values).unwrap();
+    assert_eq!(&buf[..2], &[0, 3]);
+    assert_eq!(buf.len(), 2 + 1 + 2 + 5);
+
+    let read: Vec<VarInt> = read_vec::<Short, _, _>(&mut Cursor::new(buf)).unwrap();
+    assert_eq!(read, values);
+
+    // Negative lengths are rejected.
+    assert!(read_vec::<Short, VarInt, _>(&mut Cursor::new(vec![0xFF, 0xFF])).is_err());
+}
+
+#[test]
+fn test_angle() {
+    for (degrees, byte) in [(0.0, 0), (90.0, 64), (180.0, 128), (-90.0, 192), (360.0, 0)].iter() {
+        let angle = Angle::from_degrees(*degrees);
+        assert_eq!(angle.0, *byte);
+
+        let mut buf = Cursor::new(Vec::new());
+        angle.write_to(&mut buf).unwrap();
+        assert_eq!(buf.get_ref().len(), 1);
+
+        buf.set_position(0);
+        let read = Angle::read_from(&mut buf).unwrap();
+        assert!((read.to_degrees() - degrees.rem_euclid(360.0)).abs() < 360.0 / 256.0);
+    }
+}
+
+#[test]
+fn test_bitset() {
+    let bits = [0, 5, 63, 64, 130];
+
+    let mut set = BitSet::default();
+    for b in bits.iter() {
+        set.set(*b, true);
+    }
+    set.set(5, false);
+    set.set(1000, false);
+    assert_eq!(set.0.len(), 3);
+
+    let mut buf = Cursor::new(Vec::new());
+    set.write_to(&mut buf).unwrap();
+    assert_eq!(buf.get_ref().len(), 1 + 3 * 8);
+
+    buf.set_position(0);
+    let read = BitSet::read_from(&mut buf).unwrap();
+    assert_eq!(read, set);
+
+    for b in 0..200 {
+        assert_eq!(read.get(b), b != 5 && bits.contains(&b));
+    }
+}
+
+#[test]
+fn test_entity_metadata() {
+    use crate::versions::v47::{V47Readable, V47Writable};
+
+    let metadata = EntityMetadata(vec![
+        MetadataEntry {
+            index: 0,
+            value: MetadataValue::Byte(0x02),
+        },
+        MetadataEntry {
+            index: 2,
+            value: MetadataValue::String("Dinnerbone".to_owned()),
+        },
+    ]);
+
+    let mut buf = Vec::new();
+    metadata.v47_write(&mut buf).unwrap();
+
+    let mut expected = vec![0x00, 0x02, 0x82, 10];
+    expected.extend_from_slice(b"Dinnerbone");
+    expected.push(0x7F);
+    assert_eq!(buf, expected);
+
+    // Trailing bytes after the terminator belong to the next field.
+    buf.push(0xAA);
+    let mut cursor = Cursor::new(buf);
+    assert_eq!(EntityMetadata::v47_read(&mut cursor).unwrap(), metadata);
+    assert_eq!(cursor.position(), expected.len() as u64);
+
+    let unsupported = EntityMetadata(vec![MetadataEntry {
+        index: 0,
+        value: MetadataValue::Bool(true),
+    }]);
+    assert!(unsupported.v47_write(&mut Vec::new()).is_err());
+}
+
+#[test]
+fn test_event_defaults() {
+    let handshake = Event::Handshake(Handshake {
+        protocol_version: None,
+        server_address: "localhost".to_owned(),
+        server_port: 25565,
+        next_state: EventState::Login,
+    });
+    assert_eq!(handshake.default_state(), EventState::Handshake);
+    assert_eq!(handshake.default_direction(), EventDirection::ServerBound);
+
+    let join = Event::JoinGame(JoinGame::default());
+    assert_eq!(join.default_state(), EventState::Play);
+    assert_eq!(join.default_direction(), EventDirection::ClientBound);
+
+    let login = Event::LoginStart(LoginStart {
+        name: "Steve".to_owned(),
+    });
+    assert_eq!(login.default_state(), EventState::Login);
+    assert_eq!(login.default_direction(), EventDirection::ServerBound);
+
+    let unknown = Event::Unknown {
+        id: 0x7F,
+        state: EventState::Status,
+        direction: EventDirection::ClientBound,
+        data: vec![],
+    };
+    assert_eq!(unknown.default_state(), EventState::Status);
+    assert_eq!(unknown.default_direction(), EventDirection::ClientBound);
+}
+
+#[test]
+fn test_position_encodings() {
+    use crate::versions::v47::V47Writable;
+    use crate::versions::v754::{V754Readable, V754Writable};
+
+    let positions = [
+        Position { x: 0, y: 0, z: 0 },
+        Position {
+            x: 100,
+            y: 64,
+            z: -200,
+        },
+        Position {
+            x: -33554432,
+            y: -2048,
+            z: 33554431,
+        },
+    ];
+
+    for position in positions.iter() {
+        let mut buf = Cursor::new(Vec::new());
+        position.v754_write(&mut buf).unwrap();
+        assert_eq!(buf.get_ref().len(), 8);
+
+        buf.set_position(0);
+        assert_eq!(&Position::v754_read(&mut buf).unwrap(), position);
+    }
+
+    let position = &positions[1];
+    let (mut v47, mut v754) = (Vec::new(), Vec::new());
+    position.v47_write(&mut v47).unwrap();
+    position.v754_write(&mut v754).unwrap();
+    assert_ne!(v47, v754);
+    // y is the low 12 bits.
+    assert_eq!(v754[6] & 0xF, 0);
+    assert_eq!(v754[7], 64);
+}
+
+#[test]
+fn test_plugin_message_brand() {
+    let message = PluginMessage {
+        channel: "minecraft:brand".to_owned(),
+        data: vec![0x07, b'v', b'a', b'n', b'i', b'l', b'l', b'a'],
+    };
+    assert_eq!(
+        message.parse(),
+        KnownPluginMessage::Brand("vanilla".to_owned())
+    );
+    assert_eq!(
+        PluginMessage::brand("vanilla", &ProtocolVersion::V754),
+        message
+    );
+
+    let legacy = PluginMessage {
+        channel: "MC|Brand".to_owned(),
+        data: message.data.clone(),
+    };
+    assert_eq!(
+        legacy.parse(),
+        KnownPluginMessage::Brand("vanilla".to_owned())
+    );
+    assert_eq!(
+        PluginMessage::brand("vanilla", &ProtocolVersion::V47),
+        legacy
+    );
+    assert_eq!(
+        PluginMessage::brand("vanilla", &ProtocolVersion::V340).channel,
+        "MC|Brand"
+    );
+    assert_eq!(
+        PluginMessage::brand("vanilla", &ProtocolVersion::V578).channel,
+        "minecraft:brand"
+    );
+
+    let other = PluginMessage {
+        channel: "example:channel".to_owned(),
+        data: vec![1, 2, 3],
+    };
+    assert_eq!(
+        other.parse(),
+        KnownPluginMessage::Unknown {
+            channel: "example:channel".to_owned(),
+            data: vec![1, 2, 3],
+        }
+    );
+}
+
+#[test]
+fn test_slot_nbt() {
+    let mut display = nbt::Map::new();
+    display.insert(
+        "Name".to_owned(),
+        nbt::Value::String("Excalibur".to_owned()),
+    );
+    display.insert(
+        "Lore".to_owned(),
+        nbt::Value::List(vec![nbt::Value::String("Sharp".to_owned())]),
+    );
+
+    let mut sharpness = nbt::Map::new();
+    sharpness.insert("id".to_owned(), nbt::Value::Short(16));
+    sharpness.insert("lvl".to_owned(), nbt::Value::Short(5));
+
+    let mut blob = nbt::Blob::new();
+    blob.insert("display", nbt::Value::Compound(display))
+        .unwrap();
+    blob.insert(
+        "ench",
+        nbt::Value::List(vec![nbt::Value::Compound(sharpness)]),
+    )
+    .unwrap();
+
+    let slot = Slot {
+        item_id: Some(276),
+        item_count: 1,
+        damage: Some(0),
+        nbt: Some(blob),
+    };
+
+    assert_eq!(slot.display_name(), Some("Excalibur".to_owned()));
+    assert_eq!(slot.lore(), vec!["Sharp".to_owned()]);
+    assert_eq!(slot.enchantments(), vec![("16".to_owned(), 5)]);
+
+    let plain = Slot { nbt: None, ..slot };
+    assert_eq!(plain.display_name(), None);
+    assert!(plain.lore().is_empty());
+    assert!(plain.enchantments().is_empty());
+}
+
+#[test]
+fn test_status_player_sample() {
+    let status: ServerInformation = serde_json::from_str(
+        r#"{
+            "version": {"name": "1.8.9", "protocol": 47},
+            "players": {
+                "max": 20,
+                "online": 2,
+                "sample": [
+                    {"name": "Notch", "id": "069a79f4-44e9-4726-a5be-fca90e38aaf5"},
+                    {"name": "jeb_", "id": "853c80ef-3c37-49fd-aa49-938b674adae6"}
+                ]
+            },
+            "description": "A Minecraft Server"
+        }"#,
+    )
+    .unwrap();
+
+    let sample = status.players.sample.unwrap();
+    assert_eq!(sample.len(), 2);
+    assert_eq!(
+        sample[0],
+        PlayerSample {
+            name: "Notch".to_owned(),
+            id: "069a79f4-44e9-4726-a5be-fca90e38aaf5".to_owned(),
+        }
+    );
+    assert_eq!(sample[1].name, "jeb_");
+
+    // Servers that hide their players leave the sample out.
+    let players: ServerPlayers = serde_json::from_str(r#"{"max": 20, "online": 0}"#).unwrap();
+    assert_eq!(players.sample, None);
+}
+
+#[test]
+fn test_oversized_lengths() {
+    use crate::errors::Error;
+
+    let prefixed = |len: i32| {
+        let mut buf = Cursor::new(Vec::new());
+        VarInt(len).write_to(&mut buf).unwrap();
+        buf.set_position(0);
+        buf
+    };
+
+    for len in [i32::MAX, -1].iter() {
+        assert!(matches!(
+            String::read_from(&mut prefixed(*len)),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(matches!(
+            ByteArrayVarInt::read_from(&mut prefixed(*len)),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(matches!(
+            GenericArray::<VarInt, Long>::read_from(&mut prefixed(*len)),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(matches!(
+            BitSet::read_from(&mut prefixed(*len)),
+            Err(Error::InvalidValue(_))
+        ));
+    }
+
+    // Lengths within the cap still fail on the missing data, not the length.
+    assert!(matches!(
+        String::read_from(&mut prefixed(MAX_STRING_LEN as i32)),
+        Err(Error::Io(_))
+    ));
+}
+
+#[test]
+fn test_statistics() {
+    let categories = [
+        ("stat.mineBlock.minecraft.stone", StatCategory::Mined),
+        ("stat.craftItem.minecraft.stick", StatCategory::Crafted),
+        ("stat.useItem.minecraft.bow", StatCategory::Used),
+        (
+            "stat.breakItem.minecraft.iron_pickaxe",
+            StatCategory::Broken,
+        ),
+        ("stat.walkOneCm", StatCategory::General),
+        ("achievement.openInventory", StatCategory::Custom),
+    ];
+
+    let stats = Statistics {
+        values: categories
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| Statistic {
+                name: (*name).to_owned(),
+                value: i as i32,
+            })
+            .collect(),
+    };
+
+    for (stat, (_, category)) in stats.values.iter().zip(categories.iter()) {
+        assert_eq!(stat.category(), *category);
+    }
+
+    assert_eq!(stats.get("stat.walkOneCm"), Some(4));
+    assert_eq!(stats.get("stat.jump"), None);
+}
+
+#[test]
+fn test_time_update_day_phase() {
+    let time = |time_of_day| TimeUpdate {
+        world_age: 0,
+        time_of_day,
+    };
+
+    let phases = [
+        (0, DayPhase::Day),
+        (11999, DayPhase::Day),
+        (12000, DayPhase::Sunset),
+        (12999, DayPhase::Sunset),
+        (13000, DayPhase::Night),
+        (22999, DayPhase::Night),
+        (23000, DayPhase::Sunrise),
+        (23999, DayPhase::Sunrise),
+        (24000, DayPhase::Day),
+    ];
+    for (ticks, phase) in phases.iter() {
+        assert_eq!(time(*ticks).day_phase(), *phase, "{} ticks", ticks);
+    }
+
+    // Time of day keeps counting up across days.
+    let later = time(5 * TimeUpdate::TICKS_PER_DAY + 18000);
+    assert_eq!(later.time_of_day_ticks(), 18000);
+    assert_eq!(later.day_phase(), DayPhase::Night);
+    assert!(!later.is_time_locked());
+
+    // Locked time is sent as a negative value.
+    let locked = time(-12500);
+    assert!(locked.is_time_locked());
+    assert_eq!(locked.time_of_day_ticks(), 12500);
+    assert_eq!(locked.day_phase(), DayPhase::Sunset);
+
+    assert_eq!(time(i64::MIN).time_of_day_ticks(), 7808);
+}
@@ -19,6 +19,34 @@ pub use uuid::Uuid;
 // https://wiki.vg/Protocol#Data_types
 // -----------------------------------
 
+/// Largest string, in bytes, that will be read. The protocol caps strings at
+/// 32767 UTF-16 code units, which is at most 131071 bytes of UTF-8.
+pub const MAX_STRING_LEN: usize = 131_071;
+
+/// Largest length prefixed byte array or array that will be read. No packet
+/// can be longer than this, so neither can anything inside it.
+pub const MAX_ARRAY_LEN: usize = 2_097_151;
+
+/// Largest packet, before or after decompression, that will be read. This is
+/// the same limit the vanilla server and client enforce, so a peer that sends
+/// anything longer is not speaking the protocol and the bound does not need to
+/// be configurable.
+pub const MAX_PACKET_LEN: usize = 2_097_152;
+
+/// Check a length prefix read off the wire before allocating anything for it.
+///
+/// Negative lengths should be cast with `as usize` first, which makes them
+/// too large and rejects them here too.
+#[inline]
+pub(crate) fn checked_len(len: usize, max: usize, kind: &str) -> TetsuResult<usize> {
+    if len > max {
+        return Err(Error::from(InvalidValue {
+            expected: format!("{} length of at most {}, got {}", kind, max, len),
+        }));
+    }
+    Ok(len)
+}
+
 // ---- Bool ---------------
 
 pub type Bool = bool;
@@ -228,8 +256,8 @@ impl Writable for Double {
 impl Readable for String {
     #[inline]
     fn read_from<T: io::Read>(buf: &mut T) -> TetsuResult<Self> {
-        let len = VarInt::read_from(buf)?.0;
-        let mut bytes = vec![0; len as usize];
+        let len = checked_len(VarInt::read_from(buf)?.0 as usize, MAX_STRING_LEN, "String")?;
+        let mut bytes = vec![0; len];
         buf.read_exact(&mut bytes)?;
         Ok(Self::from_utf8(bytes)?)
     }
@@ -260,6 +288,47 @@ impl Writable for Chat {
     }
 }
 
+// ---- Angle --------------
+
+impl Readable for Angle {
+    #[inline]
+    fn read_from<T: io::Read>(buf: &mut T) -> TetsuResult<Self> {
+        Ok(Self(buf.read_u8()?))
+    }
+}
+
+impl Writable for Angle {
+    #[inline]
+    fn write_to<T: io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        Ok(buf.write_u8(self.0)?)
+    }
+}
+
+// ---- Bit Set ------------
+
+impl Readable for BitSet {
+    #[inline]
+    fn read_from<T: io::Read>(buf: &mut T) -> TetsuResult<Self> {
+        let len = checked_len(VarInt::read_from(buf)?.0 as usize, MAX_ARRAY_LEN, "BitSet")?;
+        let mut data = Vec::with_capacity(len);
+        for _ in 0..len {
+            data.push(buf.read_u64::<BigEndian>()?);
+        }
+        Ok(Self(data))
+    }
+}
+
+impl Writable for BitSet {
+    #[inline]
+    fn write_to<T: io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.0.len() as i32).write_to(buf)?;
+        for l in &self.0 {
+            buf.write_u64::<BigEndian>(*l)?;
+        }
+        Ok(())
+    }
+}
+
 // ---- Identifier ---------
 
 // Same as String
@@ -269,23 +338,34 @@ impl Writable for Chat {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct VarInt(pub i32);
 
+impl VarInt {
+    /// Number of bytes the value takes up when written, from 1 to 5.
+    #[inline]
+    pub fn encoded_len(&self) -> usize {
+        let bits = 32 - (self.0 as u32).leading_zeros() as usize;
+        std::cmp::max(1, bits.div_ceil(7))
+    }
+}
+
 impl Readable for VarInt {
     #[inline]
     fn read_from<T: io::Read>(buf: &mut T) -> TetsuResult<Self> {
         let mut res: u32 = 0;
         let mut byte;
 
-        for byte_index in 0..6 {
+        for byte_index in 0..5 {
             byte = buf.read_u8()? as u32;
 
             res |= (byte & 0x7F) << (byte_index * 7);
 
             if (byte & 0x80) == 0 {
-                break;
+                return Ok(Self(res as i32));
             }
         }
 
-        Ok(Self(res as i32))
+        Err(Error::from(InvalidValue {
+            expected: "VarInt of at most 5 bytes".to_owned(),
+        }))
     }
 }
 
@@ -294,7 +374,7 @@ impl Writable for VarInt {
     fn write_to<T: io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
         let mut val = self.0 as u32;
 
-        for _ in 0..6 {
+        for _ in 0..5 {
             let byte = val & 0x7F;
 
             val >>= 7;
@@ -342,32 +422,43 @@ impl From<VarInt> for usize {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct VarLong(pub i64);
 
+impl VarLong {
+    /// Number of bytes the value takes up when written, from 1 to 10.
+    #[inline]
+    pub fn encoded_len(&self) -> usize {
+        let bits = 64 - (self.0 as u64).leading_zeros() as usize;
+        std::cmp::max(1, bits.div_ceil(7))
+    }
+}
+
 impl Readable for VarLong {
     #[inline]
     fn read_from<T: io::Read>(buf: &mut T) -> TetsuResult<Self> {
-        let mut res: u32 = 0;
+        let mut res: u64 = 0;
         let mut byte;
 
-        for byte_index in 0..11 {
-            byte = buf.read_u8()? as u32;
+        for byte_index in 0..10 {
+            byte = buf.read_u8()? as u64;
 
             res |= (byte & 0x7F) << (byte_index * 7);
 
             if (byte & 0x80) == 0 {
-                break;
+                return Ok(Self(res as i64));
             }
         }
 
-        Ok(Self(res as i64))
+        Err(Error::from(InvalidValue {
+            expected: "VarLong of at most 10 bytes".to_owned(),
+        }))
     }
 }
 
 impl Writable for VarLong {
     #[inline]
     fn write_to<T: io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
-        let mut val = self.0 as u32;
+        let mut val = self.0 as u64;
 
-        for _ in 0..11 {
+        for _ in 0..10 {
             let byte = val & 0x7F;
 
             val >>= 7;
@@ -384,6 +475,20 @@ impl Writable for VarLong {
     }
 }
 
+impl From<i64> for VarLong {
+    #[inline]
+    fn from(item: i64) -> Self {
+        Self(item)
+    }
+}
+
+impl From<VarLong> for i64 {
+    #[inline]
+    fn from(item: VarLong) -> Self {
+        item.0
+    }
+}
+
 // ---- UUID ---------------
 
 impl Readable for Uuid {
@@ -408,7 +513,11 @@ pub struct ByteArrayVarInt(pub usize, pub Vec<u8>);
 impl Readable for ByteArrayVarInt {
     #[inline]
     fn read_from<T: io::Read>(buf: &mut T) -> TetsuResult<Self> {
-        let len = VarInt::read_from(buf)?.0 as usize;
+        let len = checked_len(
+            VarInt::read_from(buf)?.0 as usize,
+            MAX_ARRAY_LEN,
+            "Byte array",
+        )?;
         let mut data = Vec::with_capacity(len);
         buf.take(len as u64).read_to_end(&mut data)?;
         Ok(Self(len, data))
@@ -451,7 +560,7 @@ where
 {
     #[inline]
     fn read_from<T: io::Read>(buf: &mut T) -> TetsuResult<Self> {
-        let len = L::read_from(buf)?.into();
+        let len = checked_len(L::read_from(buf)?.into(), MAX_ARRAY_LEN, "Array")?;
         let mut data = Vec::with_capacity(len);
         for _ in 0..len {
             data.push(C::read_from(buf)?);
@@ -498,6 +607,50 @@ where
     }
 }
 
+/// Read a Vec prefixed with its length, encoded as `L`.
+///
+/// Unlike [`GenericArray`], the length can be any type convertible to `usize`, such as [`Short`].
+pub fn read_vec<L, C, T>(buf: &mut T) -> TetsuResult<Vec<C>>
+where
+    L: Readable,
+    usize: TryFrom<L>,
+    C: Readable,
+    T: io::Read,
+{
+    let len = usize::try_from(L::read_from(buf)?).map_err(|_| {
+        Error::from(InvalidValue {
+            expected: "non-negative array length".to_owned(),
+        })
+    })?;
+    let len = checked_len(len, MAX_ARRAY_LEN, "Array")?;
+
+    let mut data = Vec::with_capacity(len);
+    for _ in 0..len {
+        data.push(C::read_from(buf)?);
+    }
+    Ok(data)
+}
+
+/// Write a slice prefixed with its length, encoded as `L`.
+pub fn write_vec<L, C, T>(buf: &mut T, items: &[C]) -> TetsuResult<()>
+where
+    L: Writable + TryFrom<usize>,
+    C: Writable,
+    T: io::Write,
+{
+    let len = L::try_from(items.len()).map_err(|_| {
+        Error::from(InvalidValue {
+            expected: format!("array length to fit its prefix, got {}", items.len()),
+        })
+    })?;
+    len.write_to(buf)?;
+
+    for i in items {
+        i.write_to(buf)?;
+    }
+    Ok(())
+}
+
 // ---- Vec ----------------
 
 impl Readable for Vec<UnsignedByte> {
@@ -541,12 +694,11 @@ impl<C: Readable + Writable> Readable for GenericOption<C> {
     #[inline]
     fn read_from<T: io::Read>(buf: &mut T) -> TetsuResult<Self> {
         let exists = bool::read_from(buf)?;
-        let internal;
-        if exists {
-            internal = Some(C::read_from(buf)?)
+        let internal = if exists {
+            Some(C::read_from(buf)?)
         } else {
-            internal = None
-        }
+            None
+        };
 
         Ok(Self(internal))
     }
@@ -675,10 +827,9 @@ impl Writable for PlayerProperty {
 impl Readable for PlayerInfoAdd {
     fn read_from<T: std::io::Read>(buf: &mut T) -> TetsuResult<Self> {
         let name = String::read_from(buf)?;
-        let properties: GenericArray<VarInt, PlayerProperty> = GenericArray::read_from(buf)?;
         Ok(Self {
             name,
-            properties: properties.into(),
+            properties: read_vec::<VarInt, _, _>(buf)?,
             gamemode: Gamemode::read_from(buf)?,
             ping: VarInt::read_from(buf)?.0,
             display: GenericOption::read_from(buf)?.0,
@@ -689,9 +840,7 @@ impl Readable for PlayerInfoAdd {
 impl Writable for PlayerInfoAdd {
     fn write_to<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
         self.name.write_to(buf)?;
-        let properites: GenericArray<VarInt, PlayerProperty> =
-            GenericArray::from(self.properties.clone());
-        properites.write_to(buf)?;
+        write_vec::<VarInt, _, _>(buf, &self.properties)?;
         self.gamemode.write_to(buf)?;
         VarInt(self.ping).write_to(buf)?;
         GenericOption(self.display.clone()).write_to(buf)
@@ -768,6 +917,180 @@ impl Writable for Difficulty {
     }
 }
 
+impl Readable for ChatPosition {
+    fn read_from<T: std::io::Read>(buf: &mut T) -> TetsuResult<Self> {
+        Ok(match Byte::read_from(buf)? {
+            0 => ChatPosition::Chat,
+            1 => ChatPosition::System,
+            2 => ChatPosition::GameInfo,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2".to_owned(),
+                }))
+            }
+        })
+    }
+}
+
+impl Writable for ChatPosition {
+    fn write_to<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        (match self {
+            ChatPosition::Chat => 0,
+            ChatPosition::System => 1,
+            ChatPosition::GameInfo => 2,
+        } as Byte)
+            .write_to(buf)
+    }
+}
+
+impl Readable for DiggingStatus {
+    fn read_from<T: std::io::Read>(buf: &mut T) -> TetsuResult<Self> {
+        Ok(match Byte::read_from(buf)? {
+            0 => DiggingStatus::StartDestroy,
+            1 => DiggingStatus::CancelDestroy,
+            2 => DiggingStatus::FinishDestroy,
+            3 => DiggingStatus::DropItemStack,
+            4 => DiggingStatus::DropItem,
+            5 => DiggingStatus::ShootArrowOrFinishEating,
+            6 => DiggingStatus::SwapItemInHand,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2, 3, 4, 5, 6".to_owned(),
+                }))
+            }
+        })
+    }
+}
+
+impl Writable for DiggingStatus {
+    fn write_to<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        (match self {
+            DiggingStatus::StartDestroy => 0,
+            DiggingStatus::CancelDestroy => 1,
+            DiggingStatus::FinishDestroy => 2,
+            DiggingStatus::DropItemStack => 3,
+            DiggingStatus::DropItem => 4,
+            DiggingStatus::ShootArrowOrFinishEating => 5,
+            DiggingStatus::SwapItemInHand => 6,
+        } as Byte)
+            .write_to(buf)
+    }
+}
+
+impl Readable for BlockFace {
+    fn read_from<T: std::io::Read>(buf: &mut T) -> TetsuResult<Self> {
+        Ok(match Byte::read_from(buf)? {
+            0 => BlockFace::Bottom,
+            1 => BlockFace::Top,
+            2 => BlockFace::North,
+            3 => BlockFace::South,
+            4 => BlockFace::West,
+            5 => BlockFace::East,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2, 3, 4, 5".to_owned(),
+                }))
+            }
+        })
+    }
+}
+
+impl Writable for BlockFace {
+    fn write_to<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        (match self {
+            BlockFace::Bottom => 0,
+            BlockFace::Top => 1,
+            BlockFace::North => 2,
+            BlockFace::South => 3,
+            BlockFace::West => 4,
+            BlockFace::East => 5,
+        } as Byte)
+            .write_to(buf)
+    }
+}
+
+impl Readable for ClientStatusAction {
+    fn read_from<T: std::io::Read>(buf: &mut T) -> TetsuResult<Self> {
+        Ok(match VarInt::read_from(buf)?.0 {
+            0 => ClientStatusAction::PerformRespawn,
+            1 => ClientStatusAction::RequestStats,
+            2 => ClientStatusAction::TakingInventoryAchievement,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2".to_owned(),
+                }))
+            }
+        })
+    }
+}
+
+impl Writable for ClientStatusAction {
+    fn write_to<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(match self {
+            ClientStatusAction::PerformRespawn => 0,
+            ClientStatusAction::RequestStats => 1,
+            ClientStatusAction::TakingInventoryAchievement => 2,
+        })
+        .write_to(buf)
+    }
+}
+
+impl Readable for Hand {
+    fn read_from<T: std::io::Read>(buf: &mut T) -> TetsuResult<Self> {
+        Ok(match VarInt::read_from(buf)?.0 {
+            0 => Hand::MainHand,
+            1 => Hand::OffHand,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1".to_owned(),
+                }))
+            }
+        })
+    }
+}
+
+impl Writable for Hand {
+    fn write_to<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(match self {
+            Hand::MainHand => 0,
+            Hand::OffHand => 1,
+        })
+        .write_to(buf)
+    }
+}
+
+impl Readable for AnimationType {
+    fn read_from<T: std::io::Read>(buf: &mut T) -> TetsuResult<Self> {
+        Ok(match UnsignedByte::read_from(buf)? {
+            0 => AnimationType::SwingMainArm,
+            1 => AnimationType::TakeDamage,
+            2 => AnimationType::LeaveBed,
+            3 => AnimationType::EatFood,
+            4 => AnimationType::CriticalEffect,
+            5 => AnimationType::MagicCriticalEffect,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2, 3, 4, 5".to_owned(),
+                }))
+            }
+        })
+    }
+}
+
+impl Writable for AnimationType {
+    fn write_to<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        (match self {
+            AnimationType::SwingMainArm => 0,
+            AnimationType::TakeDamage => 1,
+            AnimationType::LeaveBed => 2,
+            AnimationType::EatFood => 3,
+            AnimationType::CriticalEffect => 4,
+            AnimationType::MagicCriticalEffect => 5,
+        } as UnsignedByte)
+            .write_to(buf)
+    }
+}
+
 impl Default for JoinGame {
     fn default() -> Self {
         Self {
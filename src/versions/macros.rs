@@ -1,5 +1,12 @@
 //! Commonly used macros.
 
+/// Implement a protocol's read/write traits for events whose fields are
+/// serialized in order.
+///
+/// Each field is listed with the type it is encoded as on the wire, which
+/// may differ from the type of the event field. The two are converted with
+/// `From`/`Into`, so an `i32` field listed as `threshold: VarInt` is written
+/// as a VarInt rather than a fixed width `Int`.
 macro_rules! auto_read_and_write_impl {
     (
         (read: $read_trait:path, $read_fn:ident;
@@ -84,7 +91,11 @@ macro_rules! new_protocol_impl {
             $(use $read_trait;)*
 
             Box::new(| buf: &mut __T, state: &_p_impl_EventState, direction: &_p_impl_EventDirection, compression_threshold: i32 | {
-                let total_len = VarInt::read_from(buf)?.0 as usize;
+                let total_len = $crate::versions::common::checked_len(
+                    VarInt::read_from(buf)?.0 as usize,
+                    $crate::versions::common::MAX_PACKET_LEN,
+                    "Packet",
+                )?;
 
                 let mut bytes = vec![0; total_len];
                 buf.read_exact(&mut bytes)?;
@@ -95,8 +106,13 @@ macro_rules! new_protocol_impl {
                 if compression_threshold > 0 {
                     let uncompressed_len = VarInt::read_from(&mut bytes)?.0;
 
-                    if uncompressed_len > 0 {
-                        uncompressed_bytes = vec![0; uncompressed_len as usize];
+                    if uncompressed_len != 0 {
+                        let uncompressed_len = $crate::versions::common::checked_len(
+                            uncompressed_len as usize,
+                            $crate::versions::common::MAX_PACKET_LEN,
+                            "Decompressed packet",
+                        )?;
+                        uncompressed_bytes = vec![0; uncompressed_len];
                         let mut reader = _p_impl_ZlibDecoder::new(bytes);
                         reader.read_exact(&mut uncompressed_bytes)?;
                         bytes = &uncompressed_bytes[..];
@@ -105,50 +121,76 @@ macro_rules! new_protocol_impl {
 
                 let id = VarInt::read_from(&mut bytes)?.0;
 
-                Ok(match (&id, &direction, &state) {
+                log::trace!(
+                    "Read packet {:#04x} ({:?}, {:?}), {} bytes",
+                    id, direction, state, total_len
+                );
+
+                let event = match (&id, &direction, &state) {
                     $($(
                         ($id, _p_impl_EventDirection::$direction, _p_impl_EventState::$state)
                         => $name::$read_fn(&mut bytes)?,
                     )*)*
-                    _ => return Err(
-                        Error::from(InvalidValue { expected: format!("Unknown packet [{:#x}]:[{:?}]:[{:?}]", id, direction, state) })
-                    )
-                })
+                    _ => _p_impl_Event::Unknown {
+                        id,
+                        state: *state,
+                        direction: *direction,
+                        data: bytes.to_vec(),
+                    },
+                };
+
+                Ok(event)
             })
         }
 
         /// Get the write event callback
         #[inline]
-        pub fn get_write_callback<__T: std::io::Write>() -> Box<dyn Fn(&mut __T, Event, &_p_impl_EventState, &_p_impl_EventDirection, i32) -> TetsuResult<()>> {
+        pub fn get_write_callback<__T: std::io::Write>() -> Box<dyn Fn(&mut __T, Event, &_p_impl_EventState, &_p_impl_EventDirection, i32, u32) -> TetsuResult<()>> {
             $(use $write_trait;)*
 
-            Box::new(| buf: &mut __T, event: _p_impl_Event, _state: &_p_impl_EventState, _direction: &_p_impl_EventDirection, compression_threshold: i32 | {
+            Box::new(| buf: &mut __T, event: _p_impl_Event, state: &_p_impl_EventState, direction: &_p_impl_EventDirection, compression_threshold: i32, compression_level: u32 | {
                 let mut bytes = vec![];
 
-                match event {
+                // Some events are sent in several states or directions, which pick the id.
+                let id = match (event, direction, state) {
                     $($(
-                        Event::$name(e) => {
+                        (Event::$name(e), _p_impl_EventDirection::$direction, _p_impl_EventState::$state) => {
                             VarInt($id).write_to(&mut bytes)?;
-                            e.$write_fn(&mut bytes)?
+                            e.$write_fn(&mut bytes)?;
+                            $id
                         },
                     )*)*
-                    _ => return Err(
+                    (Event::Unknown { id, data, .. }, _, _) => {
+                        VarInt(id).write_to(&mut bytes)?;
+                        bytes.extend_from_slice(&data);
+                        id
+                    }
+                    (event, _, _) => return Err(
                         Error::from(InvalidValue { expected: format!("Event: {:#?} is unimplemented", event) })
                     )
                 };
 
                 if compression_threshold > 0 {
-                    let uncompressed_len = bytes.len() as i32;
-                    let mut uncompressed_buf = bytes.clone();
-
-                    bytes.clear();
-
-                    let mut writer = _p_impl_ZlibEncoder::new(&mut uncompressed_buf[..], _p_impl_Compression::default());
-
-                    VarInt(uncompressed_len).write_to(&mut bytes)?;
-                    writer.write_all(&mut bytes)?;
+                    let uncompressed_buf = std::mem::take(&mut bytes);
+
+                    // Packets below the threshold are sent uncompressed with a length of 0.
+                    if (uncompressed_buf.len() as i32) < compression_threshold {
+                        VarInt(0).write_to(&mut bytes)?;
+                        bytes.extend_from_slice(&uncompressed_buf);
+                    } else {
+                        VarInt(uncompressed_buf.len() as i32).write_to(&mut bytes)?;
+
+                        let mut writer = _p_impl_ZlibEncoder::new(bytes, _p_impl_Compression::new(compression_level));
+                        writer.write_all(&uncompressed_buf)?;
+                        bytes = writer.finish()?;
+                    }
                 }
 
+                log::trace!(
+                    "Wrote packet {:#04x} ({:?}, {:?}), {} bytes",
+                    id, direction, state, bytes.len()
+                );
+
                 VarInt(bytes.len() as i32).write_to(buf)?;
                 Ok(buf.write_all(&bytes)?)
             })
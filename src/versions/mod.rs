@@ -2,7 +2,9 @@
 
 #[macro_use]
 mod macros;
-mod common;
+pub(crate) mod common;
 
+pub(crate) mod v340;
 pub(crate) mod v47;
+pub(crate) mod v578;
 pub(crate) mod v754;
@@ -0,0 +1,187 @@
+use std::convert::{TryFrom, TryInto};
+
+use super::common::*;
+use crate::errors::*;
+use crate::event::*;
+
+use super::v47::{V47Readable, V47Writable};
+
+pub trait V340Readable<F>: Sized {
+    fn v340_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<F>;
+}
+
+pub trait V340Writable: Sized {
+    fn v340_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()>;
+}
+
+// ----- Other types -----------------------------
+
+impl V340Readable<Dimension> for Dimension {
+    fn v340_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Dimension> {
+        Ok(match Int::read_from(buf)? {
+            -1 => Dimension::Nether,
+            0 => Dimension::Overworld,
+            1 => Dimension::End,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "-1, 0, 1".to_owned(),
+                }))
+            }
+        })
+    }
+}
+
+impl V340Writable for Dimension {
+    fn v340_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        (match self {
+            Dimension::Nether => -1,
+            Dimension::Overworld => 0,
+            Dimension::End => 1,
+        } as Int)
+            .write_to(buf)
+    }
+}
+
+// Auto implemented ------------------------------
+
+auto_read_and_write_impl! {
+    (read: V340Readable<Event>, v340_read;
+    write: V340Writable, v340_write) => {
+        // Play ==========================================
+        // Client bound ----------------------------------
+        {
+            KeepAlive,
+            id: Long,
+        }
+        // Server bound ----------------------------------
+        {
+            KeepAliveResponse,
+            id: Long,
+        }
+    }
+}
+
+new_protocol_impl! {
+    (read: V47Readable, v47_read;
+    write: V47Writable, v47_write) => {
+        // Status ========================================
+        // Client bound ----------------------------------
+        (0x00, ClientBound, Status) => StatusResponse,
+        (0x01, ClientBound, Status) => Pong,
+        // Server bound ----------------------------------
+        (0x00, ServerBound, Status) => StatusRequest,
+        (0x01, ServerBound, Status) => Ping,
+
+        // Login =========================================
+        // Client bound ----------------------------------
+        (0x00, ClientBound, Login) => Disconnect,
+        (0x01, ClientBound, Login) => EncryptionRequest,
+        (0x02, ClientBound, Login) => LoginSuccess,
+        (0x03, ClientBound, Login) => SetCompression,
+        // Server bound ----------------------------------
+        (0x00, ServerBound, Login) => LoginStart,
+        (0x01, ServerBound, Login) => EncryptionResponse,
+
+        // Play ==========================================
+        // Client bound ----------------------------------
+        (0x0D, ClientBound, Play) => ServerDifficultyUpdate,
+        (0x0F, ClientBound, Play) => ChatMessage,
+        (0x18, ClientBound, Play) => PluginMessage,
+        (0x3A, ClientBound, Play) => HeldItemChange,
+        (0x46, ClientBound, Play) => SpawnPosition,
+        (0x47, ClientBound, Play) => TimeUpdate,
+        // Server bound ----------------------------------
+        (0x02, ServerBound, Play) => SendChatMessage,
+    }
+
+    (read: V340Readable, v340_read;
+    write: V340Writable, v340_write) => {
+        // Handshake =====================================
+        // Server bound ----------------------------------
+        (0x00, ServerBound, Handshake) => Handshake,
+
+        // Play ==========================================
+        // Client bound ----------------------------------
+        (0x1F, ClientBound, Play) => KeepAlive,
+        (0x23, ClientBound, Play) => JoinGame,
+        // Server bound ----------------------------------
+        (0x0B, ServerBound, Play) => KeepAliveResponse,
+    }
+}
+
+// =========== Manual Implementations ============
+
+// Handshake =====================================
+// Server bound ----------------------------------
+
+impl V340Readable<Event> for Handshake {
+    fn v340_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::Handshake(Handshake {
+            protocol_version: Some(VarInt::read_from(buf)?.0),
+            server_address: String::read_from(buf)?,
+            server_port: UnsignedShort::read_from(buf)?,
+            next_state: EventState::read_from(buf)?,
+        }))
+    }
+}
+
+impl V340Writable for Handshake {
+    fn v340_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.protocol_version.unwrap_or(340)).write_to(buf)?;
+        self.server_address.write_to(buf)?;
+        self.server_port.write_to(buf)?;
+        self.next_state.write_to(buf)
+    }
+}
+
+// Play ==========================================
+// Client bound ----------------------------------
+
+impl V340Readable<Event> for JoinGame {
+    fn v340_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let id = Int::read_from(buf)?;
+        let gamemode = UnsignedByte::read_from(buf)?;
+        let dimension = Dimension::v340_read(buf)?;
+        let difficulty = Difficulty::read_from(buf)?;
+        let max_players = UnsignedByte::read_from(buf)?;
+        let level_type = String::read_from(buf)?;
+        let reduced_debug = Bool::read_from(buf)?;
+
+        Ok(Event::JoinGame(Self {
+            id,
+            gamemode: ((gamemode & 0x7) as i32).try_into()?,
+            is_hardcore: gamemode & 0x8 == 0x8,
+            dimension: Some(dimension),
+            difficulty: Some(difficulty),
+            max_players: max_players as u32,
+            level_type: Some(level_type),
+            reduced_debug,
+            ..Default::default()
+        }))
+    }
+}
+
+impl V340Writable for JoinGame {
+    fn v340_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        let (dimension, difficulty, level_type) =
+            match (&self.dimension, &self.difficulty, &self.level_type) {
+                (Some(dimension), Some(difficulty), Some(level_type)) => {
+                    (dimension, difficulty, level_type)
+                }
+                _ => {
+                    return Err(Error::from(InvalidValue {
+                        expected: "dimension, difficulty and level type".to_owned(),
+                    }))
+                }
+            };
+
+        self.id.write_to(buf)?;
+        let gamemode = i32::try_from(self.gamemode.clone())? as UnsignedByte;
+        (gamemode | if self.is_hardcore { 0x8 } else { 0x0 }).write_to(buf)?;
+        dimension.v340_write(buf)?;
+        difficulty.write_to(buf)?;
+        (self.max_players as UnsignedByte).write_to(buf)?;
+        level_type.write_to(buf)?;
+        self.reduced_debug.write_to(buf)
+    }
+}
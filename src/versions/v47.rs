@@ -70,6 +70,47 @@ impl V47Writable for Dimension {
 
 // ----------------------------------
 
+// Positions are sent as fixed-point numbers with 5 fractional bits.
+
+#[inline]
+fn read_fixed_byte<T: std::io::Read>(buf: &mut T) -> TetsuResult<f64> {
+    Ok(Byte::read_from(buf)? as f64 / 32.0)
+}
+
+#[inline]
+fn write_fixed_byte<T: std::io::Write>(val: f64, buf: &mut T) -> TetsuResult<()> {
+    let fixed = (val * 32.0).round();
+    if fixed < Byte::MIN as f64 || fixed > Byte::MAX as f64 {
+        return Err(Error::from(InvalidValue {
+            expected: "delta of less than 4 blocks".to_owned(),
+        }));
+    }
+    (fixed as Byte).write_to(buf)
+}
+
+#[inline]
+fn read_fixed_int<T: std::io::Read>(buf: &mut T) -> TetsuResult<f64> {
+    Ok(Int::read_from(buf)? as f64 / 32.0)
+}
+
+#[inline]
+fn write_fixed_int<T: std::io::Write>(val: f64, buf: &mut T) -> TetsuResult<()> {
+    ((val * 32.0).round() as Int).write_to(buf)
+}
+
+/// Sound positions are sent with 3 fraction bits instead of 5.
+#[inline]
+fn read_sound_position<T: std::io::Read>(buf: &mut T) -> TetsuResult<f64> {
+    Ok(Int::read_from(buf)? as f64 / 8.0)
+}
+
+#[inline]
+fn write_sound_position<T: std::io::Write>(val: f64, buf: &mut T) -> TetsuResult<()> {
+    ((val * 8.0).round() as Int).write_to(buf)
+}
+
+// ----------------------------------
+
 impl V47Readable<Statistic> for Statistic {
     fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Statistic> {
         Ok(Self {
@@ -130,6 +171,11 @@ auto_read_and_write_impl! {
         }
         // Play ==========================================
         // Client bound ----------------------------------
+        {
+            ChatMessage,
+            message: Chat,
+            position: ChatPosition,
+        }
         {
             TimeUpdate,
             world_age: Long,
@@ -144,6 +190,110 @@ auto_read_and_write_impl! {
             channel: String,
             data: Vec<u8>,
         }
+        {
+            TabCompleteResponse,
+            matches: GenericArray<VarInt, String>,
+        }
+        {
+            UpdateHealth,
+            health: Float,
+            food: VarInt,
+            saturation: Float,
+        }
+        {
+            SetExperience,
+            experience_bar: Float,
+            level: VarInt,
+            total_experience: VarInt,
+        }
+        {
+            EntityVelocity,
+            entity_id: VarInt,
+            velocity_x: Short,
+            velocity_y: Short,
+            velocity_z: Short,
+        }
+        {
+            EntityAnimation,
+            entity_id: VarInt,
+            animation: AnimationType,
+        }
+        {
+            EntityHeadLook,
+            entity_id: VarInt,
+            head_yaw: Angle,
+        }
+        {
+            CloseWindow,
+            window_id: UnsignedByte,
+        }
+        {
+            SetHeldItem,
+            slot: Short,
+        }
+        {
+            Camera,
+            camera_id: VarInt,
+        }
+        {
+            CollectItem,
+            collected_entity_id: VarInt,
+            collector_entity_id: VarInt,
+        }
+        {
+            EntityStatus,
+            entity_id: Int,
+            status: UnsignedByte,
+        }
+        {
+            AttachEntity,
+            attached_entity_id: Int,
+            holding_entity_id: Int,
+            leash: Bool,
+        }
+        {
+            Spectate,
+            target: Uuid,
+        }
+        {
+            ConfirmTransaction,
+            window_id: UnsignedByte,
+            action_number: Short,
+            accepted: Bool,
+        }
+        {
+            WindowProperty,
+            window_id: UnsignedByte,
+            property: Short,
+            value: Short,
+        }
+        // Server bound ----------------------------------
+        {
+            PlayerPosition,
+            x: Double,
+            feet_y: Double,
+            z: Double,
+            on_ground: Bool,
+        }
+        {
+            PlayerLook,
+            yaw: Float,
+            pitch: Float,
+            on_ground: Bool,
+        }
+        {
+            PlayerPositionAndLookServerbound,
+            x: Double,
+            feet_y: Double,
+            z: Double,
+            yaw: Float,
+            pitch: Float,
+            on_ground: Bool,
+        }
+        {
+            ClientStatus,
+            action: ClientStatusAction,
+        }
     }
 }
 
@@ -176,18 +326,66 @@ new_protocol_impl! {
         // Client bound ----------------------------------
         (0x00, ClientBound, Play) => KeepAlive,
         (0x01, ClientBound, Play) => JoinGame,
+        (0x02, ClientBound, Play) => ChatMessage,
         (0x03, ClientBound, Play) => TimeUpdate,
+        (0x04, ClientBound, Play) => EntityEquipment,
         (0x05, ClientBound, Play) => SpawnPosition,
+        (0x06, ClientBound, Play) => UpdateHealth,
+        (0x07, ClientBound, Play) => Respawn,
         (0x08, ClientBound, Play) => PlayerPositionAndLook,
         (0x09, ClientBound, Play) => HeldItemChange,
+        (0x0B, ClientBound, Play) => EntityAnimation,
+        (0x0C, ClientBound, Play) => SpawnPlayer,
+        (0x0D, ClientBound, Play) => CollectItem,
+        (0x0F, ClientBound, Play) => SpawnMob,
+        (0x12, ClientBound, Play) => EntityVelocity,
+        (0x13, ClientBound, Play) => DestroyEntities,
+        (0x15, ClientBound, Play) => EntityRelativeMove,
+        (0x17, ClientBound, Play) => EntityLookAndRelativeMove,
+        (0x18, ClientBound, Play) => EntityTeleport,
+        (0x19, ClientBound, Play) => EntityHeadLook,
+        (0x1A, ClientBound, Play) => EntityStatus,
+        (0x1B, ClientBound, Play) => AttachEntity,
+        (0x1F, ClientBound, Play) => SetExperience,
+        (0x21, ClientBound, Play) => ChunkData,
+        (0x22, ClientBound, Play) => MultiBlockChange,
+        (0x23, ClientBound, Play) => BlockChange,
+        (0x26, ClientBound, Play) => MapChunkBulk,
+        (0x27, ClientBound, Play) => Explosion,
+        (0x29, ClientBound, Play) => NamedSoundEffect,
+        (0x2D, ClientBound, Play) => OpenWindow,
+        (0x2E, ClientBound, Play) => CloseWindow,
         (0x2f, ClientBound, Play) => SlotUpdate,
         (0x30, ClientBound, Play) => WindowItemsUpdate,
+        (0x31, ClientBound, Play) => WindowProperty,
+        (0x32, ClientBound, Play) => ConfirmTransaction,
         (0x37, ClientBound, Play) => Statistics,
         (0x38, ClientBound, Play) => PlayerInfoUpdate,
         (0x39, ClientBound, Play) => PlayerAbility,
+        (0x3A, ClientBound, Play) => TabCompleteResponse,
         (0x3F, ClientBound, Play) => PluginMessage,
+        (0x40, ClientBound, Play) => Disconnect,
         (0x41, ClientBound, Play) => ServerDifficultyUpdate,
+        (0x43, ClientBound, Play) => Camera,
         (0x44, ClientBound, Play) => WorldBorder,
+        // Server bound ----------------------------------
+        (0x00, ServerBound, Play) => KeepAliveResponse,
+        (0x01, ServerBound, Play) => SendChatMessage,
+        (0x02, ServerBound, Play) => InteractEntity,
+        (0x04, ServerBound, Play) => PlayerPosition,
+        (0x05, ServerBound, Play) => PlayerLook,
+        (0x06, ServerBound, Play) => PlayerPositionAndLookServerbound,
+        (0x07, ServerBound, Play) => PlayerDigging,
+        (0x08, ServerBound, Play) => PlayerBlockPlacement,
+        (0x09, ServerBound, Play) => SetHeldItem,
+        (0x0A, ServerBound, Play) => Animation,
+        (0x0D, ServerBound, Play) => CloseWindow,
+        (0x0E, ServerBound, Play) => ClickWindow,
+        (0x0F, ServerBound, Play) => ConfirmTransaction,
+        (0x14, ServerBound, Play) => TabComplete,
+        (0x15, ServerBound, Play) => ClientSettings,
+        (0x16, ServerBound, Play) => ClientStatus,
+        (0x18, ServerBound, Play) => Spectate,
     }
 }
 
@@ -198,8 +396,8 @@ new_protocol_impl! {
 
 impl V47Readable<Event> for Handshake {
     fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
-        let _ = VarInt::read_from(buf)?;
         Ok(Event::Handshake(Handshake {
+            protocol_version: Some(VarInt::read_from(buf)?.0),
             server_address: String::read_from(buf)?,
             server_port: UnsignedShort::read_from(buf)?,
             next_state: EventState::read_from(buf)?,
@@ -209,7 +407,7 @@ impl V47Readable<Event> for Handshake {
 
 impl V47Writable for Handshake {
     fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
-        VarInt(47).write_to(buf)?;
+        VarInt(self.protocol_version.unwrap_or(47)).write_to(buf)?;
         self.server_address.write_to(buf)?;
         self.server_port.write_to(buf)?;
         self.next_state.write_to(buf)
@@ -240,23 +438,21 @@ impl V47Writable for StatusResponse {
 
 impl V47Readable<Event> for Disconnect {
     fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let reason = String::read_from(buf)?;
+
+        // Some servers send a plain string instead of a chat component.
         Ok(Event::Disconnect(Disconnect {
-            reason: Chat {
-                text: Some(String::read_from(buf)?),
+            reason: serde_json::from_str(&reason).unwrap_or_else(|_| Chat {
+                text: Some(reason),
                 ..Default::default()
-            },
+            }),
         }))
     }
 }
 
 impl V47Writable for Disconnect {
     fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
-        match &self.reason.text {
-            Some(t) => t.write_to(buf),
-            _ => Err(Error::from(InvalidValue {
-                expected: "Text field".to_owned(),
-            })),
-        }
+        self.reason.write_to(buf)
     }
 }
 
@@ -264,8 +460,15 @@ impl V47Writable for Disconnect {
 
 impl V47Readable<Event> for LoginSuccess {
     fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        // Accepts both the hyphenated and the undashed form.
+        let uuid = Uuid::parse_str(&String::read_from(buf)?[..]).map_err(|_| {
+            Error::from(InvalidValue {
+                expected: "UUID string".to_owned(),
+            })
+        })?;
+
         Ok(Event::LoginSuccess(LoginSuccess {
-            uuid: Uuid::parse_str(&String::read_from(buf)?[..]).unwrap(),
+            uuid,
             name: String::read_from(buf)?,
         }))
     }
@@ -309,8 +512,9 @@ impl V47Readable<Event> for JoinGame {
 
         Ok(Event::JoinGame(Self {
             id,
-            gamemode: (gamemode as i32).try_into()?,
-            is_hardcore: gamemode & 0x80 == 0x80,
+            // Bit 3 is the hardcore flag.
+            gamemode: ((gamemode & 0x07) as i32).try_into()?,
+            is_hardcore: gamemode & 0x08 == 0x08,
             dimension: Some(dimension),
             difficulty: Some(difficulty),
             max_players: max_players as u32,
@@ -323,28 +527,172 @@ impl V47Readable<Event> for JoinGame {
 
 impl V47Writable for JoinGame {
     fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
-        self.id.write_to(buf)?;
+        let missing = |field: &str| {
+            Error::from(InvalidValue {
+                expected: format!("{} for V47 JoinGame", field),
+            })
+        };
 
-        (i32::try_from(self.gamemode.clone())? | if self.is_hardcore { 0x80 } else { 0x00 })
+        self.id.write_to(buf)?;
+        (i32::try_from(self.gamemode.clone())? as UnsignedByte
+            | if self.is_hardcore { 0x08 } else { 0x00 })
+        .write_to(buf)?;
+        self.dimension
+            .as_ref()
+            .ok_or_else(|| missing("dimension"))?
+            .v47_write(buf)?;
+        self.difficulty
+            .as_ref()
+            .ok_or_else(|| missing("difficulty"))?
             .write_to(buf)?;
+        (self.max_players as UnsignedByte).write_to(buf)?;
+        self.level_type
+            .as_ref()
+            .ok_or_else(|| missing("level type"))?
+            .write_to(buf)?;
+        self.reduced_debug.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for NamedSoundEffect {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::NamedSoundEffect(Self {
+            sound: String::read_from(buf)?,
+            x: read_sound_position(buf)?,
+            y: read_sound_position(buf)?,
+            z: read_sound_position(buf)?,
+            volume: Float::read_from(buf)?,
+            pitch: UnsignedByte::read_from(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for NamedSoundEffect {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.sound.write_to(buf)?;
+        write_sound_position(self.x, buf)?;
+        write_sound_position(self.y, buf)?;
+        write_sound_position(self.z, buf)?;
+        self.volume.write_to(buf)?;
+        self.pitch.write_to(buf)
+    }
+}
+
+// ----------------------------------
 
-        match &self.dimension {
-            Some(d) => d.v47_write(buf)?,
-            _ => panic!("Expected dimension"),
+impl V47Readable<Event> for Explosion {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let x = Float::read_from(buf)?;
+        let y = Float::read_from(buf)?;
+        let z = Float::read_from(buf)?;
+        let radius = Float::read_from(buf)?;
+
+        let count = Int::read_from(buf)?;
+        if count < 0 {
+            return Err(Error::from(InvalidValue {
+                expected: format!("non-negative record count, got {}", count),
+            }));
         }
-        match &self.difficulty {
-            Some(d) => d.write_to(buf)?,
-            _ => panic!("Expected difficulty"),
+
+        // Not preallocated, so a bad count fails on the missing records instead.
+        let mut records = Vec::new();
+        for _ in 0..count {
+            records.push((
+                Byte::read_from(buf)?,
+                Byte::read_from(buf)?,
+                Byte::read_from(buf)?,
+            ));
         }
 
-        self.max_players.write_to(buf)?;
+        Ok(Event::Explosion(Self {
+            x,
+            y,
+            z,
+            radius,
+            records,
+            player_motion: (
+                Float::read_from(buf)?,
+                Float::read_from(buf)?,
+                Float::read_from(buf)?,
+            ),
+        }))
+    }
+}
 
-        match &self.level_type {
-            Some(l) => l.write_to(buf)?,
-            _ => panic!("Level Expected."),
+impl V47Writable for Explosion {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.x.write_to(buf)?;
+        self.y.write_to(buf)?;
+        self.z.write_to(buf)?;
+        self.radius.write_to(buf)?;
+
+        (self.records.len() as Int).write_to(buf)?;
+        for (x, y, z) in &self.records {
+            x.write_to(buf)?;
+            y.write_to(buf)?;
+            z.write_to(buf)?;
         }
 
-        self.reduced_debug.write_to(buf)
+        self.player_motion.0.write_to(buf)?;
+        self.player_motion.1.write_to(buf)?;
+        self.player_motion.2.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for Respawn {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        // Unlike `JoinGame`, the dimension is sent as an `Int`.
+        let dimension = match Int::read_from(buf)? {
+            -1 => Dimension::Nether,
+            0 => Dimension::Overworld,
+            1 => Dimension::End,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "-1, 0, 1".to_owned(),
+                }))
+            }
+        };
+
+        Ok(Event::Respawn(Self {
+            dimension,
+            difficulty: Some(Difficulty::read_from(buf)?),
+            gamemode: (UnsignedByte::read_from(buf)? as i32).try_into()?,
+            level_type: Some(String::read_from(buf)?),
+            dimension_codec: None,
+            world_name: None,
+            hashed_seed: None,
+            previous_gamemode: None,
+            is_debug: None,
+            is_flat: None,
+            copy_metadata: None,
+        }))
+    }
+}
+
+impl V47Writable for Respawn {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        let (difficulty, level_type) = match (&self.difficulty, &self.level_type) {
+            (Some(difficulty), Some(level_type)) => (difficulty, level_type),
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "difficulty and level type".to_owned(),
+                }))
+            }
+        };
+
+        (match self.dimension {
+            Dimension::Nether => -1,
+            Dimension::Overworld => 0,
+            Dimension::End => 1,
+        } as Int)
+            .write_to(buf)?;
+        difficulty.write_to(buf)?;
+        (i32::try_from(self.gamemode.clone())? as UnsignedByte).write_to(buf)?;
+        level_type.write_to(buf)
     }
 }
 
@@ -368,54 +716,61 @@ impl V47Writable for SpawnPosition {
 
 impl V47Readable<Event> for PlayerPositionAndLook {
     fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
-        let x = Double::read_from(buf)?;
-        let y = Double::read_from(buf)?;
-        let z = Double::read_from(buf)?;
-        let yaw = Float::read_from(buf)?;
-        let pitch = Float::read_from(buf)?;
-        let flags = Byte::read_from(buf)?;
-
-        let x = if flags | 0x01 == 0x01 {
-            RelativeOrAbsolute::Relative(x)
-        } else {
-            RelativeOrAbsolute::Absolute(x)
-        };
-        let y = if flags | 0x02 == 0x02 {
-            RelativeOrAbsolute::Relative(y)
-        } else {
-            RelativeOrAbsolute::Absolute(y)
-        };
-        let z = if flags | 0x04 == 0x04 {
-            RelativeOrAbsolute::Relative(z)
-        } else {
-            RelativeOrAbsolute::Absolute(z)
-        };
-
-        let yaw = if flags | 0x08 == 0x08 {
-            RelativeOrAbsolute::Relative(yaw)
-        } else {
-            RelativeOrAbsolute::Absolute(yaw)
-        };
-        let pitch = if flags | 0x10 == 0x10 {
-            RelativeOrAbsolute::Relative(pitch)
-        } else {
-            RelativeOrAbsolute::Absolute(pitch)
-        };
-
-        Ok(Event::PlayerPositionAndLook(Self {
-            x,
-            y,
-            z,
-            yaw,
-            pitch,
-            teleport_id: None,
-        }))
+        Ok(Event::PlayerPositionAndLook(read_position_and_look(buf)?))
     }
 }
 
+/// Read the position, rotation and flags shared by all versions of [`PlayerPositionAndLook`].
+pub(super) fn read_position_and_look<T: std::io::Read>(
+    buf: &mut T,
+) -> TetsuResult<PlayerPositionAndLook> {
+    let x = Double::read_from(buf)?;
+    let y = Double::read_from(buf)?;
+    let z = Double::read_from(buf)?;
+    let yaw = Float::read_from(buf)?;
+    let pitch = Float::read_from(buf)?;
+    let flags = Byte::read_from(buf)?;
+
+    let x = if flags & 0x01 == 0x01 {
+        RelativeOrAbsolute::Relative(x)
+    } else {
+        RelativeOrAbsolute::Absolute(x)
+    };
+    let y = if flags & 0x02 == 0x02 {
+        RelativeOrAbsolute::Relative(y)
+    } else {
+        RelativeOrAbsolute::Absolute(y)
+    };
+    let z = if flags & 0x04 == 0x04 {
+        RelativeOrAbsolute::Relative(z)
+    } else {
+        RelativeOrAbsolute::Absolute(z)
+    };
+
+    let yaw = if flags & 0x08 == 0x08 {
+        RelativeOrAbsolute::Relative(yaw)
+    } else {
+        RelativeOrAbsolute::Absolute(yaw)
+    };
+    let pitch = if flags & 0x10 == 0x10 {
+        RelativeOrAbsolute::Relative(pitch)
+    } else {
+        RelativeOrAbsolute::Absolute(pitch)
+    };
+
+    Ok(PlayerPositionAndLook {
+        x,
+        y,
+        z,
+        yaw,
+        pitch,
+        teleport_id: None,
+    })
+}
+
 impl V47Writable for PlayerPositionAndLook {
     fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
-        let mut flags = 0;
+        let mut flags: Byte = 0;
 
         let x = match self.x {
             RelativeOrAbsolute::Relative(x) => {
@@ -541,11 +896,9 @@ impl V47Writable for ChangeGameState {
 
 // ----------------------------------
 
-// Untested
-
 impl V47Readable<Slot> for Slot {
     fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Slot> {
-        let id = Byte::read_from(buf)?;
+        let id = Short::read_from(buf)?;
 
         if id == -1 {
             return Ok(Self {
@@ -559,13 +912,19 @@ impl V47Readable<Slot> for Slot {
         let item_count = Byte::read_from(buf)?;
         let damage = Short::read_from(buf)?;
 
-        let nbt = NbtBlob::read_from(buf)?;
+        // A single end tag means the item has no NBT.
+        let tag = UnsignedByte::read_from(buf)?;
+        let nbt = if tag == 0 {
+            None
+        } else {
+            Some(NbtBlob::read_from(&mut (&[tag][..]).chain(buf))?)
+        };
 
         Ok(Self {
             item_id: Some(id as i32),
             item_count,
             damage: Some(damage),
-            nbt: Some(nbt),
+            nbt,
         })
     }
 }
@@ -574,15 +933,169 @@ impl V47Writable for Slot {
     fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
         match &self.item_id {
             None => {
-                (-1 as Byte).write_to(buf)?;
+                (-1 as Short).write_to(buf)?;
                 return Ok(());
             }
-            Some(id) => id.write_to(buf)?,
+            Some(id) => (*id as Short).write_to(buf)?,
         }
 
         self.item_count.write_to(buf)?;
-        self.damage.unwrap().write_to(buf)?;
-        self.nbt.clone().unwrap().write_to(buf)
+        self.damage.unwrap_or(0).write_to(buf)?;
+        match &self.nbt {
+            Some(nbt) => nbt.write_to(buf),
+            None => (0 as UnsignedByte).write_to(buf),
+        }
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<EntityMetadata> for EntityMetadata {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<EntityMetadata> {
+        let mut entries = Vec::new();
+
+        loop {
+            // Upper 3 bits are the value type, lower 5 the index.
+            let key = UnsignedByte::read_from(buf)?;
+            if key == 0x7F {
+                break;
+            }
+
+            let value = match key >> 5 {
+                0 => MetadataValue::Byte(Byte::read_from(buf)?),
+                1 => MetadataValue::Short(Short::read_from(buf)?),
+                2 => MetadataValue::Int(Int::read_from(buf)?),
+                3 => MetadataValue::Float(Float::read_from(buf)?),
+                4 => MetadataValue::String(String::read_from(buf)?),
+                5 => MetadataValue::Slot(Slot::v47_read(buf)?),
+                6 => MetadataValue::Position(Position {
+                    x: Int::read_from(buf)? as i64,
+                    y: Int::read_from(buf)? as i64,
+                    z: Int::read_from(buf)? as i64,
+                }),
+                7 => MetadataValue::Rotation(
+                    Float::read_from(buf)?,
+                    Float::read_from(buf)?,
+                    Float::read_from(buf)?,
+                ),
+                _ => {
+                    return Err(Error::from(InvalidValue {
+                        expected: "metadata type in range 0..=7".to_owned(),
+                    }))
+                }
+            };
+
+            entries.push(MetadataEntry {
+                index: key & 0x1F,
+                value,
+            });
+        }
+
+        Ok(Self(entries))
+    }
+}
+
+impl V47Writable for EntityMetadata {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        for entry in &self.0 {
+            if entry.index > 0x1F {
+                return Err(Error::from(InvalidValue {
+                    expected: "metadata index in range 0..=31".to_owned(),
+                }));
+            }
+
+            let key = |ty: UnsignedByte| ty << 5 | entry.index;
+
+            match &entry.value {
+                MetadataValue::Byte(v) => {
+                    key(0).write_to(buf)?;
+                    v.write_to(buf)?;
+                }
+                MetadataValue::Short(v) => {
+                    key(1).write_to(buf)?;
+                    v.write_to(buf)?;
+                }
+                MetadataValue::Int(v) => {
+                    key(2).write_to(buf)?;
+                    v.write_to(buf)?;
+                }
+                MetadataValue::Float(v) => {
+                    key(3).write_to(buf)?;
+                    v.write_to(buf)?;
+                }
+                MetadataValue::String(v) => {
+                    key(4).write_to(buf)?;
+                    v.write_to(buf)?;
+                }
+                MetadataValue::Slot(v) => {
+                    key(5).write_to(buf)?;
+                    v.v47_write(buf)?;
+                }
+                MetadataValue::Position(v) => {
+                    key(6).write_to(buf)?;
+                    (v.x as Int).write_to(buf)?;
+                    (v.y as Int).write_to(buf)?;
+                    (v.z as Int).write_to(buf)?;
+                }
+                MetadataValue::Rotation(x, y, z) => {
+                    key(7).write_to(buf)?;
+                    x.write_to(buf)?;
+                    y.write_to(buf)?;
+                    z.write_to(buf)?;
+                }
+                _ => {
+                    return Err(Error::from(InvalidValue {
+                        expected: "metadata value supported by protocol 47".to_owned(),
+                    }))
+                }
+            }
+        }
+
+        (0x7F as UnsignedByte).write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for OpenWindow {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let window_id = UnsignedByte::read_from(buf)?;
+        let window_type = String::read_from(buf)?;
+        let title = Chat::read_from(buf)?;
+        let slot_count = UnsignedByte::read_from(buf)?;
+        let entity_id = if window_type == "EntityHorse" {
+            Some(Int::read_from(buf)?)
+        } else {
+            None
+        };
+
+        Ok(Event::OpenWindow(Self {
+            window_id,
+            window_type,
+            title,
+            slot_count,
+            entity_id,
+        }))
+    }
+}
+
+impl V47Writable for OpenWindow {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        // The reader only expects an entity id for horse windows.
+        if (self.window_type == "EntityHorse") != self.entity_id.is_some() {
+            return Err(Error::from(InvalidValue {
+                expected: "entity id only for EntityHorse windows".to_owned(),
+            }));
+        }
+
+        self.window_id.write_to(buf)?;
+        self.window_type.write_to(buf)?;
+        self.title.write_to(buf)?;
+        self.slot_count.write_to(buf)?;
+        if let Some(entity_id) = self.entity_id {
+            entity_id.write_to(buf)?;
+        }
+        Ok(())
     }
 }
 
@@ -591,7 +1104,7 @@ impl V47Readable<Event> for SlotUpdate {
         Ok(Event::SlotUpdate(Self {
             window_id: Byte::read_from(buf)?,
             slot: Short::read_from(buf)?,
-            data: Slot::v47_read(buf)?
+            data: Slot::v47_read(buf)?,
         }))
     }
 }
@@ -608,6 +1121,11 @@ impl V47Readable<Event> for WindowItemsUpdate {
     fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
         let window_id = UnsignedByte::read_from(buf)?;
         let slots_len = Short::read_from(buf)?;
+        if slots_len < 0 {
+            return Err(Error::from(InvalidValue {
+                expected: format!("non-negative slot count, got {}", slots_len),
+            }));
+        }
         let mut slots = Vec::with_capacity(slots_len as usize);
         for _ in 0..slots_len {
             slots.push(Slot::v47_read(buf)?);
@@ -634,10 +1152,11 @@ impl V47Writable for WindowItemsUpdate {
 
 impl V47Readable<Event> for Statistics {
     fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
-        let len = VarInt::read_from(buf)?.0 as usize;
-
-        println!("Statistics with len: {}", len);
-
+        let len = checked_len(
+            VarInt::read_from(buf)?.0 as usize,
+            MAX_ARRAY_LEN,
+            "Statistics",
+        )?;
         let mut values = Vec::with_capacity(len);
 
         for _ in 0..len {
@@ -675,7 +1194,11 @@ impl V47Readable<Event> for PlayerInfoUpdate {
                 2 => PlayerInfoAction::LatencyUpdate(PlayerLatencyUpdate::read_from(buf)?),
                 3 => PlayerInfoAction::DisplayNameUpdate(PlayerDisplayNameUpdate::read_from(buf)?),
                 4 => PlayerInfoAction::Remove(RemovePlayer {}),
-                _ => panic!("Unknown"),
+                _ => {
+                    return Err(Error::from(InvalidValue {
+                        expected: "0-4".to_owned(),
+                    }))
+                }
             };
             players.push(PlayerListInfo {
                 uuid,
@@ -797,7 +1320,11 @@ impl V47Readable<Event> for WorldBorder {
             5 => Self::SetWarnBlocks {
                 warning_blocks: VarInt::read_from(buf)?.0,
             },
-            _ => panic!("Unknown type"),
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0-5".to_owned(),
+                }))
+            }
         }))
     }
 }
@@ -851,3 +1378,770 @@ impl V47Writable for WorldBorder {
         Ok(())
     }
 }
+
+// ----------------------------------
+
+impl V47Readable<Event> for SpawnPlayer {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::SpawnPlayer(Self {
+            entity_id: VarInt::read_from(buf)?.0,
+            uuid: Uuid::read_from(buf)?,
+            x: read_fixed_int(buf)?,
+            y: read_fixed_int(buf)?,
+            z: read_fixed_int(buf)?,
+            yaw: Angle::read_from(buf)?,
+            pitch: Angle::read_from(buf)?,
+            current_item: Short::read_from(buf)?,
+            metadata: EntityMetadata::v47_read(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for SpawnPlayer {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.entity_id).write_to(buf)?;
+        self.uuid.write_to(buf)?;
+        write_fixed_int(self.x, buf)?;
+        write_fixed_int(self.y, buf)?;
+        write_fixed_int(self.z, buf)?;
+        self.yaw.write_to(buf)?;
+        self.pitch.write_to(buf)?;
+        self.current_item.write_to(buf)?;
+        self.metadata.v47_write(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for SpawnMob {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::SpawnMob(Self {
+            entity_id: VarInt::read_from(buf)?.0,
+            uuid: None,
+            entity_type: UnsignedByte::read_from(buf)? as i32,
+            x: read_fixed_int(buf)?,
+            y: read_fixed_int(buf)?,
+            z: read_fixed_int(buf)?,
+            yaw: Angle::read_from(buf)?,
+            pitch: Angle::read_from(buf)?,
+            head_pitch: Angle::read_from(buf)?,
+            velocity: (
+                Short::read_from(buf)?,
+                Short::read_from(buf)?,
+                Short::read_from(buf)?,
+            ),
+            metadata: EntityMetadata::v47_read(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for SpawnMob {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        let entity_type = UnsignedByte::try_from(self.entity_type).map_err(|_| {
+            Error::from(InvalidValue {
+                expected: "entity type of at most 255".to_owned(),
+            })
+        })?;
+
+        VarInt(self.entity_id).write_to(buf)?;
+        entity_type.write_to(buf)?;
+        write_fixed_int(self.x, buf)?;
+        write_fixed_int(self.y, buf)?;
+        write_fixed_int(self.z, buf)?;
+        self.yaw.write_to(buf)?;
+        self.pitch.write_to(buf)?;
+        self.head_pitch.write_to(buf)?;
+        self.velocity.0.write_to(buf)?;
+        self.velocity.1.write_to(buf)?;
+        self.velocity.2.write_to(buf)?;
+        self.metadata.v47_write(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for DestroyEntities {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let len = VarInt::read_from(buf)?.0;
+        let mut entity_ids = Vec::new();
+        for _ in 0..len {
+            entity_ids.push(VarInt::read_from(buf)?.0);
+        }
+
+        Ok(Event::DestroyEntities(Self { entity_ids }))
+    }
+}
+
+impl V47Writable for DestroyEntities {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.entity_ids.len() as i32).write_to(buf)?;
+        for id in &self.entity_ids {
+            VarInt(*id).write_to(buf)?;
+        }
+        Ok(())
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for EntityRelativeMove {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::EntityRelativeMove(Self {
+            entity_id: VarInt::read_from(buf)?.0,
+            delta_x: read_fixed_byte(buf)?,
+            delta_y: read_fixed_byte(buf)?,
+            delta_z: read_fixed_byte(buf)?,
+            on_ground: Bool::read_from(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for EntityRelativeMove {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.entity_id).write_to(buf)?;
+        write_fixed_byte(self.delta_x, buf)?;
+        write_fixed_byte(self.delta_y, buf)?;
+        write_fixed_byte(self.delta_z, buf)?;
+        self.on_ground.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for EntityLookAndRelativeMove {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::EntityLookAndRelativeMove(Self {
+            entity_id: VarInt::read_from(buf)?.0,
+            delta_x: read_fixed_byte(buf)?,
+            delta_y: read_fixed_byte(buf)?,
+            delta_z: read_fixed_byte(buf)?,
+            yaw: Angle::read_from(buf)?,
+            pitch: Angle::read_from(buf)?,
+            on_ground: Bool::read_from(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for EntityLookAndRelativeMove {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.entity_id).write_to(buf)?;
+        write_fixed_byte(self.delta_x, buf)?;
+        write_fixed_byte(self.delta_y, buf)?;
+        write_fixed_byte(self.delta_z, buf)?;
+        self.yaw.write_to(buf)?;
+        self.pitch.write_to(buf)?;
+        self.on_ground.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for EntityTeleport {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::EntityTeleport(Self {
+            entity_id: VarInt::read_from(buf)?.0,
+            x: read_fixed_int(buf)?,
+            y: read_fixed_int(buf)?,
+            z: read_fixed_int(buf)?,
+            yaw: Angle::read_from(buf)?,
+            pitch: Angle::read_from(buf)?,
+            on_ground: Bool::read_from(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for EntityTeleport {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.entity_id).write_to(buf)?;
+        write_fixed_int(self.x, buf)?;
+        write_fixed_int(self.y, buf)?;
+        write_fixed_int(self.z, buf)?;
+        self.yaw.write_to(buf)?;
+        self.pitch.write_to(buf)?;
+        self.on_ground.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+const SECTION_BLOCKS: usize = 4096;
+const SECTION_LIGHT: usize = SECTION_BLOCKS / 2;
+
+/// Read the present sections of a column, followed by its biomes if it is ground up continuous.
+fn read_chunk_column(
+    data: &mut &[u8],
+    mask: u16,
+    has_sky_light: bool,
+    ground_up_continuous: bool,
+) -> TetsuResult<Chunk> {
+    let count = mask.count_ones() as usize;
+    let section_len = SECTION_BLOCKS * 2 + SECTION_LIGHT * if has_sky_light { 2 } else { 1 };
+    let biomes_len = if ground_up_continuous { 256 } else { 0 };
+
+    if data.len() < count * section_len + biomes_len {
+        return Err(Error::from(InvalidValue {
+            expected: "chunk data matching the primary bit mask".to_owned(),
+        }));
+    }
+
+    // Blocks, block light and sky light are each sent for every section in turn.
+    let mut take = |len: usize| {
+        let (head, tail) = data.split_at(len);
+        *data = tail;
+        head
+    };
+
+    let blocks: Vec<Vec<u16>> = (0..count)
+        .map(|_| {
+            take(SECTION_BLOCKS * 2)
+                .chunks(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .collect()
+        })
+        .collect();
+    let block_light: Vec<Vec<u8>> = (0..count).map(|_| take(SECTION_LIGHT).to_vec()).collect();
+    let sky_light: Vec<Option<Vec<u8>>> = (0..count)
+        .map(|_| {
+            if has_sky_light {
+                Some(take(SECTION_LIGHT).to_vec())
+            } else {
+                None
+            }
+        })
+        .collect();
+    let biomes = if ground_up_continuous {
+        let mut biomes = [0; 256];
+        biomes.copy_from_slice(take(256));
+        Some(biomes)
+    } else {
+        None
+    };
+
+    let mut present = blocks.into_iter().zip(block_light).zip(sky_light).map(
+        |((blocks, block_light), sky_light)| ChunkSection {
+            blocks,
+            block_light,
+            sky_light,
+        },
+    );
+
+    Ok(Chunk {
+        sections: (0..16)
+            .map(|i| {
+                if mask & 1 << i != 0 {
+                    present.next()
+                } else {
+                    None
+                }
+            })
+            .collect(),
+        biomes,
+    })
+}
+
+/// Write the present sections of a column, followed by its biomes if it is ground up continuous.
+fn write_chunk_column(
+    chunk: &Chunk,
+    ground_up_continuous: bool,
+    data: &mut Vec<u8>,
+) -> TetsuResult<()> {
+    let sections: Vec<&ChunkSection> = chunk.sections.iter().flatten().collect();
+    let has_sky_light = sections.first().is_some_and(|s| s.sky_light.is_some());
+
+    for section in &sections {
+        if section.blocks.len() != SECTION_BLOCKS {
+            return Err(Error::from(InvalidValue {
+                expected: format!("{} blocks per section", SECTION_BLOCKS),
+            }));
+        }
+        if section.block_light.len() != SECTION_LIGHT {
+            return Err(Error::from(InvalidValue {
+                expected: format!("{} bytes of block light per section", SECTION_LIGHT),
+            }));
+        }
+        // The reader infers sky light from the data length, so it must be on all sections or none.
+        match &section.sky_light {
+            Some(l) if l.len() != SECTION_LIGHT => {
+                return Err(Error::from(InvalidValue {
+                    expected: format!("{} bytes of sky light per section", SECTION_LIGHT),
+                }))
+            }
+            l if l.is_some() != has_sky_light => {
+                return Err(Error::from(InvalidValue {
+                    expected: "sky light on every section or none".to_owned(),
+                }))
+            }
+            _ => {}
+        }
+    }
+    for section in &sections {
+        for block in &section.blocks {
+            data.extend_from_slice(&block.to_le_bytes());
+        }
+    }
+    for section in &sections {
+        data.extend_from_slice(&section.block_light);
+    }
+    for section in &sections {
+        if let Some(sky_light) = &section.sky_light {
+            data.extend_from_slice(sky_light);
+        }
+    }
+    if ground_up_continuous {
+        data.extend_from_slice(&chunk.biomes.unwrap_or([0; 256]));
+    }
+    Ok(())
+}
+
+impl V47Readable<Event> for ChunkData {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let chunk_x = Int::read_from(buf)?;
+        let chunk_z = Int::read_from(buf)?;
+        let ground_up_continuous = Bool::read_from(buf)?;
+        let mask = UnsignedShort::read_from(buf)?;
+
+        let data: Vec<u8> = ByteArrayVarInt::read_from(buf)?.into();
+
+        // The column data is the last field.
+        if buf.read(&mut [0])? != 0 {
+            return Err(Error::from(InvalidValue {
+                expected: "end of packet after the chunk data".to_owned(),
+            }));
+        }
+
+        let count = mask.count_ones() as usize;
+        let biomes_len = if ground_up_continuous { 256 } else { 0 };
+
+        // Sky light isn't flagged, so infer it from the size of the data.
+        let has_sky_light =
+            if data.len() == count * (SECTION_BLOCKS * 2 + SECTION_LIGHT * 2) + biomes_len {
+                true
+            } else if data.len() == count * (SECTION_BLOCKS * 2 + SECTION_LIGHT) + biomes_len {
+                false
+            } else {
+                return Err(Error::from(InvalidValue {
+                    expected: "chunk data matching the primary bit mask".to_owned(),
+                }));
+            };
+
+        Ok(Event::ChunkData(Self {
+            chunk_x,
+            chunk_z,
+            ground_up_continuous,
+            chunk: read_chunk_column(&mut &data[..], mask, has_sky_light, ground_up_continuous)?,
+        }))
+    }
+}
+
+impl V47Writable for ChunkData {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        let mut data = Vec::new();
+        write_chunk_column(&self.chunk, self.ground_up_continuous, &mut data)?;
+
+        self.chunk_x.write_to(buf)?;
+        self.chunk_z.write_to(buf)?;
+        self.ground_up_continuous.write_to(buf)?;
+        self.chunk.primary_bit_mask()?.write_to(buf)?;
+        ByteArrayVarInt::from(data).write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for MapChunkBulk {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let sky_light = Bool::read_from(buf)?;
+        let count = VarInt::read_from(buf)?.0;
+
+        let mut meta = Vec::new();
+        for _ in 0..count {
+            meta.push((
+                Int::read_from(buf)?,
+                Int::read_from(buf)?,
+                UnsignedShort::read_from(buf)?,
+            ));
+        }
+
+        // Column data isn't length prefixed, and takes up the rest of the packet.
+        let mut data = Vec::new();
+        buf.read_to_end(&mut data)?;
+        let mut rest = &data[..];
+
+        let chunks = meta
+            .into_iter()
+            .map(|(chunk_x, chunk_z, mask)| {
+                Ok(ChunkData {
+                    chunk_x,
+                    chunk_z,
+                    ground_up_continuous: true,
+                    chunk: read_chunk_column(&mut rest, mask, sky_light, true)?,
+                })
+            })
+            .collect::<TetsuResult<_>>()?;
+
+        Ok(Event::MapChunkBulk(Self { sky_light, chunks }))
+    }
+}
+
+impl V47Writable for MapChunkBulk {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.sky_light.write_to(buf)?;
+        VarInt(self.chunks.len() as i32).write_to(buf)?;
+
+        let mut data = Vec::new();
+        for column in &self.chunks {
+            let sections = column.chunk.sections.iter().flatten();
+            if sections
+                .into_iter()
+                .any(|s| s.sky_light.is_some() != self.sky_light)
+            {
+                return Err(Error::from(InvalidValue {
+                    expected: format!("sky light on every section to be {}", self.sky_light),
+                }));
+            }
+
+            column.chunk_x.write_to(buf)?;
+            column.chunk_z.write_to(buf)?;
+            column.chunk.primary_bit_mask()?.write_to(buf)?;
+            write_chunk_column(&column.chunk, true, &mut data)?;
+        }
+        Ok(buf.write_all(&data)?)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for MultiBlockChange {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let chunk_x = Int::read_from(buf)?;
+        let chunk_z = Int::read_from(buf)?;
+
+        let len = VarInt::read_from(buf)?.0;
+        let mut records = Vec::new();
+        for _ in 0..len {
+            records.push(BlockChangeRecord {
+                horizontal_position: UnsignedByte::read_from(buf)?,
+                y_position: UnsignedByte::read_from(buf)?,
+                block_state: VarInt::read_from(buf)?.0,
+            });
+        }
+
+        Ok(Event::MultiBlockChange(Self {
+            chunk_x,
+            chunk_z,
+            records,
+        }))
+    }
+}
+
+impl V47Writable for MultiBlockChange {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.chunk_x.write_to(buf)?;
+        self.chunk_z.write_to(buf)?;
+        VarInt(self.records.len() as i32).write_to(buf)?;
+        for record in &self.records {
+            record.horizontal_position.write_to(buf)?;
+            record.y_position.write_to(buf)?;
+            VarInt(record.block_state).write_to(buf)?;
+        }
+        Ok(())
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for BlockChange {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::BlockChange(Self {
+            location: Position::v47_read(buf)?,
+            block_state: VarInt::read_from(buf)?.0,
+        }))
+    }
+}
+
+impl V47Writable for BlockChange {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.location.v47_write(buf)?;
+        VarInt(self.block_state).write_to(buf)
+    }
+}
+
+// Server bound ----------------------------------
+
+impl V47Readable<Event> for KeepAliveResponse {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::KeepAliveResponse(KeepAliveResponse {
+            id: VarInt::read_from(buf)?.0 as i64,
+        }))
+    }
+}
+
+impl V47Writable for KeepAliveResponse {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.id as i32).write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for SendChatMessage {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::SendChatMessage(Self {
+            message: String::read_from(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for SendChatMessage {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        if self.message.chars().count() > 256 {
+            return Err(Error::from(InvalidValue {
+                expected: "message of at most 256 characters".to_owned(),
+            }));
+        }
+        self.message.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for PlayerDigging {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::PlayerDigging(Self {
+            status: DiggingStatus::read_from(buf)?,
+            location: Position::v47_read(buf)?,
+            face: BlockFace::read_from(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for PlayerDigging {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.status.write_to(buf)?;
+        self.location.v47_write(buf)?;
+        self.face.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for PlayerBlockPlacement {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::PlayerBlockPlacement(Self {
+            location: Position::v47_read(buf)?,
+            face: BlockFace::read_from(buf)?,
+            held_item: Slot::v47_read(buf)?,
+            cursor_x: Byte::read_from(buf)?,
+            cursor_y: Byte::read_from(buf)?,
+            cursor_z: Byte::read_from(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for PlayerBlockPlacement {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.location.v47_write(buf)?;
+        self.face.write_to(buf)?;
+        self.held_item.v47_write(buf)?;
+        self.cursor_x.write_to(buf)?;
+        self.cursor_y.write_to(buf)?;
+        self.cursor_z.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for TabComplete {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let text = String::read_from(buf)?;
+        let looked_at = if Bool::read_from(buf)? {
+            Some(Position::v47_read(buf)?)
+        } else {
+            None
+        };
+
+        Ok(Event::TabComplete(Self { text, looked_at }))
+    }
+}
+
+impl V47Writable for TabComplete {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.text.write_to(buf)?;
+        self.looked_at.is_some().write_to(buf)?;
+        if let Some(position) = &self.looked_at {
+            position.v47_write(buf)?;
+        }
+        Ok(())
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for ClickWindow {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::ClickWindow(Self {
+            window_id: UnsignedByte::read_from(buf)?,
+            slot: Short::read_from(buf)?,
+            button: Byte::read_from(buf)?,
+            action_number: Short::read_from(buf)?,
+            mode: Byte::read_from(buf)? as i32,
+            clicked_item: Slot::v47_read(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for ClickWindow {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        // The mode is only a VarInt since 1.9.
+        let mode = Byte::try_from(self.mode).map_err(|_| {
+            Error::from(InvalidValue {
+                expected: "click mode of at most 127".to_owned(),
+            })
+        })?;
+
+        self.window_id.write_to(buf)?;
+        self.slot.write_to(buf)?;
+        self.button.write_to(buf)?;
+        self.action_number.write_to(buf)?;
+        mode.write_to(buf)?;
+        self.clicked_item.v47_write(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for ClientSettings {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::ClientSettings(Self {
+            locale: String::read_from(buf)?,
+            view_distance: Byte::read_from(buf)?,
+            chat_mode: Byte::read_from(buf)? as i32,
+            chat_colors: Bool::read_from(buf)?,
+            displayed_skin_parts: UnsignedByte::read_from(buf)?,
+            main_hand: 1,
+        }))
+    }
+}
+
+impl V47Writable for ClientSettings {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        // The chat mode is only a VarInt since 1.9.
+        let chat_mode = Byte::try_from(self.chat_mode).map_err(|_| {
+            Error::from(InvalidValue {
+                expected: "chat mode of at most 127".to_owned(),
+            })
+        })?;
+
+        self.locale.write_to(buf)?;
+        self.view_distance.write_to(buf)?;
+        chat_mode.write_to(buf)?;
+        self.chat_colors.write_to(buf)?;
+        self.displayed_skin_parts.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for Animation {
+    fn v47_read<T: std::io::Read>(_buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::Animation(Self {
+            hand: Hand::MainHand,
+        }))
+    }
+}
+
+impl V47Writable for Animation {
+    fn v47_write<T: std::io::Write>(&self, _buf: &mut T) -> TetsuResult<()> {
+        match self.hand {
+            Hand::MainHand => Ok(()),
+            Hand::OffHand => Err(Error::from(InvalidValue {
+                expected: "main hand, 1.8 has no off hand".to_owned(),
+            })),
+        }
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for InteractEntity {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let entity_id = VarInt::read_from(buf)?.0;
+        let interaction = match VarInt::read_from(buf)?.0 {
+            0 => EntityInteraction::Interact,
+            1 => EntityInteraction::Attack,
+            2 => EntityInteraction::InteractAt {
+                x: Float::read_from(buf)?,
+                y: Float::read_from(buf)?,
+                z: Float::read_from(buf)?,
+            },
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2".to_owned(),
+                }))
+            }
+        };
+
+        Ok(Event::InteractEntity(Self {
+            entity_id,
+            interaction,
+        }))
+    }
+}
+
+impl V47Writable for InteractEntity {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.entity_id).write_to(buf)?;
+
+        match self.interaction {
+            EntityInteraction::Interact => VarInt(0).write_to(buf),
+            EntityInteraction::Attack => VarInt(1).write_to(buf),
+            EntityInteraction::InteractAt { x, y, z } => {
+                VarInt(2).write_to(buf)?;
+                x.write_to(buf)?;
+                y.write_to(buf)?;
+                z.write_to(buf)
+            }
+        }
+    }
+}
+
+// ----------------------------------
+
+impl V47Readable<Event> for EntityEquipment {
+    fn v47_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let entity_id = VarInt::read_from(buf)?.0;
+        let slot = match Short::read_from(buf)? {
+            0 => EquipmentSlot::Held,
+            1 => EquipmentSlot::Boots,
+            2 => EquipmentSlot::Leggings,
+            3 => EquipmentSlot::Chestplate,
+            4 => EquipmentSlot::Helmet,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2, 3, 4".to_owned(),
+                }))
+            }
+        };
+
+        Ok(Event::EntityEquipment(Self {
+            entity_id,
+            slot,
+            item: Slot::v47_read(buf)?,
+        }))
+    }
+}
+
+impl V47Writable for EntityEquipment {
+    fn v47_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.entity_id).write_to(buf)?;
+        (match self.slot {
+            EquipmentSlot::Held => 0,
+            EquipmentSlot::Boots => 1,
+            EquipmentSlot::Leggings => 2,
+            EquipmentSlot::Chestplate => 3,
+            EquipmentSlot::Helmet => 4,
+        } as Short)
+            .write_to(buf)?;
+        self.item.v47_write(buf)
+    }
+}
@@ -0,0 +1,167 @@
+use std::convert::{TryFrom, TryInto};
+
+use super::common::*;
+use crate::errors::*;
+use crate::event::*;
+
+use super::v340::{V340Readable, V340Writable};
+use super::v47::{V47Readable, V47Writable};
+
+pub trait V578Readable<F>: Sized {
+    fn v578_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<F>;
+}
+
+pub trait V578Writable: Sized {
+    fn v578_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()>;
+}
+
+// Auto implemented ------------------------------
+
+auto_read_and_write_impl! {
+    (read: V578Readable<Event>, v578_read;
+    write: V578Writable, v578_write) => {
+        // Play ==========================================
+        // Client bound ----------------------------------
+        {
+            KeepAlive,
+            id: Long,
+        }
+        {
+            ServerDifficultyUpdate,
+            difficulty: Difficulty,
+            difficulty_locked: bool,
+        }
+        // Server bound ----------------------------------
+        {
+            KeepAliveResponse,
+            id: Long,
+        }
+    }
+}
+
+new_protocol_impl! {
+    (read: V47Readable, v47_read;
+    write: V47Writable, v47_write) => {
+        // Status ========================================
+        // Client bound ----------------------------------
+        (0x00, ClientBound, Status) => StatusResponse,
+        (0x01, ClientBound, Status) => Pong,
+        // Server bound ----------------------------------
+        (0x00, ServerBound, Status) => StatusRequest,
+        (0x01, ServerBound, Status) => Ping,
+
+        // Login =========================================
+        // Client bound ----------------------------------
+        (0x00, ClientBound, Login) => Disconnect,
+        (0x01, ClientBound, Login) => EncryptionRequest,
+        (0x02, ClientBound, Login) => LoginSuccess,
+        (0x03, ClientBound, Login) => SetCompression,
+        // Server bound ----------------------------------
+        (0x00, ServerBound, Login) => LoginStart,
+        (0x01, ServerBound, Login) => EncryptionResponse,
+
+        // Play ==========================================
+        // Client bound ----------------------------------
+        (0x0F, ClientBound, Play) => ChatMessage,
+        (0x19, ClientBound, Play) => PluginMessage,
+        (0x40, ClientBound, Play) => HeldItemChange,
+        (0x4F, ClientBound, Play) => TimeUpdate,
+        // Server bound ----------------------------------
+        (0x03, ServerBound, Play) => SendChatMessage,
+    }
+
+    (read: V578Readable, v578_read;
+    write: V578Writable, v578_write) => {
+        // Handshake =====================================
+        // Server bound ----------------------------------
+        (0x00, ServerBound, Handshake) => Handshake,
+
+        // Play ==========================================
+        // Client bound ----------------------------------
+        (0x0E, ClientBound, Play) => ServerDifficultyUpdate,
+        (0x21, ClientBound, Play) => KeepAlive,
+        (0x26, ClientBound, Play) => JoinGame,
+        // Server bound ----------------------------------
+        (0x0F, ServerBound, Play) => KeepAliveResponse,
+    }
+}
+
+// =========== Manual Implementations ============
+
+// Handshake =====================================
+// Server bound ----------------------------------
+
+impl V578Readable<Event> for Handshake {
+    fn v578_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::Handshake(Handshake {
+            protocol_version: Some(VarInt::read_from(buf)?.0),
+            server_address: String::read_from(buf)?,
+            server_port: UnsignedShort::read_from(buf)?,
+            next_state: EventState::read_from(buf)?,
+        }))
+    }
+}
+
+impl V578Writable for Handshake {
+    fn v578_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        VarInt(self.protocol_version.unwrap_or(578)).write_to(buf)?;
+        self.server_address.write_to(buf)?;
+        self.server_port.write_to(buf)?;
+        self.next_state.write_to(buf)
+    }
+}
+
+// Play ==========================================
+// Client bound ----------------------------------
+
+impl V578Readable<Event> for JoinGame {
+    fn v578_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let id = Int::read_from(buf)?;
+        let gamemode = UnsignedByte::read_from(buf)?;
+        let dimension = Dimension::v340_read(buf)?;
+        let hashed_seed = Long::read_from(buf)?;
+        let max_players = UnsignedByte::read_from(buf)?;
+        let level_type = String::read_from(buf)?;
+        let view_distance = VarInt::read_from(buf)?.0;
+        let reduced_debug = Bool::read_from(buf)?;
+        let enable_respawn = Bool::read_from(buf)?;
+
+        Ok(Event::JoinGame(Self {
+            id,
+            gamemode: ((gamemode & 0x7) as i32).try_into()?,
+            is_hardcore: gamemode & 0x8 == 0x8,
+            dimension: Some(dimension),
+            hashed_seed: Some(hashed_seed),
+            max_players: max_players as u32,
+            level_type: Some(level_type),
+            view_distance: Some(view_distance),
+            reduced_debug,
+            enable_respawn: Some(enable_respawn),
+            ..Default::default()
+        }))
+    }
+}
+
+impl V578Writable for JoinGame {
+    fn v578_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        let (dimension, level_type) = match (&self.dimension, &self.level_type) {
+            (Some(dimension), Some(level_type)) => (dimension, level_type),
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "dimension and level type".to_owned(),
+                }))
+            }
+        };
+
+        self.id.write_to(buf)?;
+        let gamemode = i32::try_from(self.gamemode.clone())? as UnsignedByte;
+        (gamemode | if self.is_hardcore { 0x8 } else { 0x0 }).write_to(buf)?;
+        dimension.v340_write(buf)?;
+        self.hashed_seed.unwrap_or_default().write_to(buf)?;
+        (self.max_players as UnsignedByte).write_to(buf)?;
+        level_type.write_to(buf)?;
+        VarInt(self.view_distance.unwrap_or(10)).write_to(buf)?;
+        self.reduced_debug.write_to(buf)?;
+        self.enable_respawn.unwrap_or(true).write_to(buf)
+    }
+}
@@ -4,7 +4,9 @@ use super::common::*;
 use crate::errors::*;
 use crate::event::*;
 
-use super::v47::{V47Readable, V47Writable};
+use super::v47::{read_position_and_look, V47Readable, V47Writable};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
 pub trait V754Readable<F>: Sized {
     fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<F>;
@@ -14,6 +16,32 @@ pub trait V754Writable: Sized {
     fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()>;
 }
 
+// ----- Other types -----------------------------
+
+// Since 1.14, y is packed into the low bits instead of the middle.
+
+impl V754Readable<Position> for Position {
+    fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Position> {
+        let val = buf.read_i64::<BigEndian>()?;
+
+        Ok(Self {
+            x: val >> 38,
+            y: (val << 52) >> 52,
+            z: (val << 26) >> 38,
+        })
+    }
+}
+
+impl V754Writable for Position {
+    fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        Ok(buf.write_i64::<BigEndian>(
+            ((self.x & 0x3FFFFFF) << 38) | ((self.z & 0x3FFFFFF) << 12) | (self.y & 0xFFF),
+        )?)
+    }
+}
+
+// Auto implemented ------------------------------
+
 auto_read_and_write_impl! {
     (read: V754Readable<Event>, v754_read;
     write: V754Writable, v754_write) => {
@@ -48,6 +76,32 @@ auto_read_and_write_impl! {
             difficulty: Difficulty,
             difficulty_locked: bool,
         }
+        // Server bound ----------------------------------
+        {
+            TeleportConfirm,
+            teleport_id: VarInt,
+        }
+        {
+            KeepAliveResponse,
+            id: Long,
+        }
+        {
+            ClientSettings,
+            locale: String,
+            view_distance: Byte,
+            chat_mode: VarInt,
+            chat_colors: Bool,
+            displayed_skin_parts: UnsignedByte,
+            main_hand: VarInt,
+        }
+        {
+            Animation,
+            hand: Hand,
+        }
+        {
+            SetHeldItem,
+            slot: Short,
+        }
     }
 }
 
@@ -89,9 +143,23 @@ new_protocol_impl! {
 
         // Play ==========================================
         // Client bound ----------------------------------
+        (0x0C, ClientBound, Play) => BossBar,
         (0x0D, ClientBound, Play) => ServerDifficultyUpdate,
+        (0x19, ClientBound, Play) => Disconnect,
+        (0x1D, ClientBound, Play) => ChangeGameState,
         (0x1F, ClientBound, Play) => KeepAlive,
         (0x24, ClientBound, Play) => JoinGame,
+        (0x34, ClientBound, Play) => PlayerPositionAndLook,
+        (0x39, ClientBound, Play) => Respawn,
+        (0x42, ClientBound, Play) => SpawnPosition,
+        // All title actions are sent as a single packet.
+        (0x4F, ClientBound, Play) => Title,
+        // Server bound ----------------------------------
+        (0x00, ServerBound, Play) => TeleportConfirm,
+        (0x05, ServerBound, Play) => ClientSettings,
+        (0x10, ServerBound, Play) => KeepAliveResponse,
+        (0x25, ServerBound, Play) => SetHeldItem,
+        (0x2C, ServerBound, Play) => Animation,
     }
 }
 
@@ -100,12 +168,10 @@ new_protocol_impl! {
 // Handshake =====================================
 // Server bound ----------------------------------
 
-// probably should also add the protocol version field for servers.
-
 impl V754Readable<Event> for Handshake {
     fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
-        let _ = VarInt::read_from(buf)?;
         Ok(Event::Handshake(Handshake {
+            protocol_version: Some(VarInt::read_from(buf)?.0),
             server_address: String::read_from(buf)?,
             server_port: UnsignedShort::read_from(buf)?,
             next_state: EventState::read_from(buf)?,
@@ -115,7 +181,7 @@ impl V754Readable<Event> for Handshake {
 
 impl V754Writable for Handshake {
     fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
-        VarInt(754).write_to(buf)?;
+        VarInt(self.protocol_version.unwrap_or(754)).write_to(buf)?;
         self.server_address.write_to(buf)?;
         self.server_port.write_to(buf)?;
         self.next_state.write_to(buf)
@@ -127,19 +193,170 @@ impl V754Writable for Handshake {
 
 // ----------------------------------
 
+impl V754Readable<Event> for BossBar {
+    fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let uuid = Uuid::read_from(buf)?;
+        let action = match VarInt::read_from(buf)?.0 {
+            0 => BossBarAction::Add {
+                title: Chat::read_from(buf)?,
+                health: Float::read_from(buf)?,
+                color: VarInt::read_from(buf)?.0,
+                division: VarInt::read_from(buf)?.0,
+                flags: UnsignedByte::read_from(buf)?,
+            },
+            1 => BossBarAction::Remove,
+            2 => BossBarAction::UpdateHealth(Float::read_from(buf)?),
+            3 => BossBarAction::UpdateTitle(Chat::read_from(buf)?),
+            4 => BossBarAction::UpdateStyle {
+                color: VarInt::read_from(buf)?.0,
+                division: VarInt::read_from(buf)?.0,
+            },
+            5 => BossBarAction::UpdateFlags(UnsignedByte::read_from(buf)?),
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2, 3, 4, 5".to_owned(),
+                }))
+            }
+        };
+
+        Ok(Event::BossBar(Self { uuid, action }))
+    }
+}
+
+impl V754Writable for BossBar {
+    fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.uuid.write_to(buf)?;
+
+        match &self.action {
+            BossBarAction::Add {
+                title,
+                health,
+                color,
+                division,
+                flags,
+            } => {
+                VarInt(0).write_to(buf)?;
+                title.write_to(buf)?;
+                health.write_to(buf)?;
+                VarInt(*color).write_to(buf)?;
+                VarInt(*division).write_to(buf)?;
+                flags.write_to(buf)
+            }
+            BossBarAction::Remove => VarInt(1).write_to(buf),
+            BossBarAction::UpdateHealth(health) => {
+                VarInt(2).write_to(buf)?;
+                health.write_to(buf)
+            }
+            BossBarAction::UpdateTitle(title) => {
+                VarInt(3).write_to(buf)?;
+                title.write_to(buf)
+            }
+            BossBarAction::UpdateStyle { color, division } => {
+                VarInt(4).write_to(buf)?;
+                VarInt(*color).write_to(buf)?;
+                VarInt(*division).write_to(buf)
+            }
+            BossBarAction::UpdateFlags(flags) => {
+                VarInt(5).write_to(buf)?;
+                flags.write_to(buf)
+            }
+        }
+    }
+}
+
+// ----------------------------------
+
+impl V754Readable<Event> for ChangeGameState {
+    fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        // Every reason is followed by a value, even if it isn't used.
+        let reason = UnsignedByte::read_from(buf)?;
+        let value = Float::read_from(buf)?;
+
+        Ok(Event::ChangeGameState(match reason {
+            0 => ChangeGameState::NoRespawnBlock,
+            1 => ChangeGameState::EndRaining,
+            2 => ChangeGameState::BeginRaining,
+            3 => ChangeGameState::GamemodeUpdate(Gamemode::try_from(value as i32)?),
+            4 => ChangeGameState::WinGame(if value == 0.0 {
+                AfterGameWin::Respawn
+            } else {
+                AfterGameWin::CreditsAndRespawn
+            }),
+            5 => ChangeGameState::DemoEvent(match value as i32 {
+                0 => DemoEventAction::Show,
+                101 => DemoEventAction::ShowMovementControls,
+                102 => DemoEventAction::ShowJumpControl,
+                103 => DemoEventAction::ShowInventoryControl,
+                _ => DemoEventAction::Over,
+            }),
+            6 => ChangeGameState::ArrowHitPlayer,
+            7 => ChangeGameState::RainLevel(value),
+            8 => ChangeGameState::ThunderLevel(value),
+            9 => ChangeGameState::PufferfishSting,
+            10 => ChangeGameState::ElderGuardianAppear,
+            // 0 shows the respawn screen, 1 respawns immediately.
+            11 => ChangeGameState::EnableRespawn(value == 0.0),
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0-11".to_owned(),
+                }))
+            }
+        }))
+    }
+}
+
+impl V754Writable for ChangeGameState {
+    fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        let (reason, value): (UnsignedByte, Float) = match self {
+            ChangeGameState::NoRespawnBlock => (0, 0.0),
+            ChangeGameState::EndRaining => (1, 0.0),
+            ChangeGameState::BeginRaining => (2, 0.0),
+            ChangeGameState::GamemodeUpdate(g) => (3, i32::try_from(g.clone())? as Float),
+            ChangeGameState::WinGame(AfterGameWin::Respawn) => (4, 0.0),
+            ChangeGameState::WinGame(AfterGameWin::CreditsAndRespawn) => (4, 1.0),
+            ChangeGameState::DemoEvent(d) => (
+                5,
+                match d {
+                    DemoEventAction::Show => 0.0,
+                    DemoEventAction::ShowMovementControls => 101.0,
+                    DemoEventAction::ShowJumpControl => 102.0,
+                    DemoEventAction::ShowInventoryControl => 103.0,
+                    DemoEventAction::Over => 104.0,
+                },
+            ),
+            ChangeGameState::ArrowHitPlayer => (6, 0.0),
+            ChangeGameState::RainLevel(v) => (7, *v),
+            ChangeGameState::ThunderLevel(v) => (8, *v),
+            ChangeGameState::PufferfishSting => (9, 0.0),
+            ChangeGameState::ElderGuardianAppear => (10, 0.0),
+            ChangeGameState::EnableRespawn(e) => (11, if *e { 0.0 } else { 1.0 }),
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "not supported for this protocol version".to_owned(),
+                }))
+            }
+        };
+
+        reason.write_to(buf)?;
+        value.write_to(buf)
+    }
+}
+
+// ----------------------------------
+
 impl V754Readable<Event> for JoinGame {
     fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
         let id = Int::read_from(buf)?;
         let is_hardcore = Bool::read_from(buf)?;
         let gamemode = UnsignedByte::read_from(buf)?;
         let _ = Byte::read_from(buf)?;
-        let worlds: GenericArray<VarInt, String> = GenericArray::read_from(buf)?;
+        let worlds = read_vec::<VarInt, _, _>(buf)?;
 
         Ok(Event::JoinGame(Self {
             id,
             is_hardcore,
             gamemode: Gamemode::try_from(gamemode as i32)?,
-            worlds: Some(worlds.into()),
+            worlds: Some(worlds),
             dimension_registry: Some(NbtBlob::read_from(buf)?),
             dimension_codec: Some(NbtBlob::read_from(buf)?),
             world_name: Some(String::read_from(buf)?),
@@ -162,8 +379,7 @@ impl V754Writable for JoinGame {
         let gamemode: i32 = self.gamemode.clone().try_into()?;
         (gamemode as u8).write_to(buf)?;
         (-1i8).write_to(buf)?; // TODO: move
-        let worlds: GenericArray<VarInt, String> = GenericArray::from(self.worlds.clone().unwrap());
-        worlds.write_to(buf)?;
+        write_vec::<VarInt, _, _>(buf, self.worlds.as_ref().unwrap())?;
         self.dimension_registry.clone().unwrap().write_to(buf)?;
         self.dimension_codec.clone().unwrap().write_to(buf)?;
         self.world_name.clone().unwrap().write_to(buf)?;
@@ -175,3 +391,170 @@ impl V754Writable for JoinGame {
         self.is_flat.unwrap().write_to(buf)
     }
 }
+
+// ----------------------------------
+
+impl V754Readable<Event> for PlayerPositionAndLook {
+    fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let position = read_position_and_look(buf)?;
+
+        Ok(Event::PlayerPositionAndLook(Self {
+            teleport_id: Some(VarInt::read_from(buf)?.0),
+            ..position
+        }))
+    }
+}
+
+impl V754Writable for PlayerPositionAndLook {
+    fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        let teleport_id = self.teleport_id.ok_or_else(|| {
+            Error::from(InvalidValue {
+                expected: "teleport id".to_owned(),
+            })
+        })?;
+
+        self.v47_write(buf)?;
+        VarInt(teleport_id).write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V754Readable<Event> for Respawn {
+    fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let dimension_codec = NbtBlob::read_from(buf)?;
+
+        // The dimension type's effects are one of the vanilla dimensions.
+        let dimension = match dimension_codec.get("effects") {
+            Some(nbt::Value::String(s)) if s == "minecraft:the_nether" => Dimension::Nether,
+            Some(nbt::Value::String(s)) if s == "minecraft:the_end" => Dimension::End,
+            Some(nbt::Value::String(s)) if s == "minecraft:overworld" => Dimension::Overworld,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "dimension type with vanilla effects".to_owned(),
+                }))
+            }
+        };
+
+        let world_name = String::read_from(buf)?;
+        let hashed_seed = Long::read_from(buf)?;
+        let gamemode = UnsignedByte::read_from(buf)?;
+        let previous_gamemode = Byte::read_from(buf)?;
+
+        Ok(Event::Respawn(Self {
+            dimension,
+            difficulty: None,
+            gamemode: Gamemode::try_from(gamemode as i32)?,
+            level_type: None,
+            dimension_codec: Some(dimension_codec),
+            world_name: Some(world_name),
+            hashed_seed: Some(hashed_seed),
+            previous_gamemode: match previous_gamemode {
+                -1 => None,
+                g => Some(Gamemode::try_from(g as i32)?),
+            },
+            is_debug: Some(Bool::read_from(buf)?),
+            is_flat: Some(Bool::read_from(buf)?),
+            copy_metadata: Some(Bool::read_from(buf)?),
+        }))
+    }
+}
+
+impl V754Writable for Respawn {
+    fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        let (dimension_codec, world_name) = match (&self.dimension_codec, &self.world_name) {
+            (Some(dimension_codec), Some(world_name)) => (dimension_codec, world_name),
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "dimension codec and world name".to_owned(),
+                }))
+            }
+        };
+
+        dimension_codec.write_to(buf)?;
+        world_name.write_to(buf)?;
+        self.hashed_seed.unwrap_or_default().write_to(buf)?;
+        (i32::try_from(self.gamemode.clone())? as UnsignedByte).write_to(buf)?;
+        (match &self.previous_gamemode {
+            Some(g) => i32::try_from(g.clone())? as Byte,
+            None => -1,
+        })
+        .write_to(buf)?;
+        self.is_debug.unwrap_or_default().write_to(buf)?;
+        self.is_flat.unwrap_or_default().write_to(buf)?;
+        self.copy_metadata.unwrap_or_default().write_to(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V754Readable<Event> for SpawnPosition {
+    fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        Ok(Event::SpawnPosition(Self {
+            location: Position::v754_read(buf)?,
+        }))
+    }
+}
+
+impl V754Writable for SpawnPosition {
+    fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        self.location.v754_write(buf)
+    }
+}
+
+// ----------------------------------
+
+impl V754Readable<Event> for Title {
+    fn v754_read<T: std::io::Read>(buf: &mut T) -> TetsuResult<Event> {
+        let action = match VarInt::read_from(buf)?.0 {
+            0 => TitleAction::SetTitle(Chat::read_from(buf)?),
+            1 => TitleAction::SetSubtitle(Chat::read_from(buf)?),
+            2 => TitleAction::SetActionBar(Chat::read_from(buf)?),
+            3 => TitleAction::SetTimes {
+                fade_in: Int::read_from(buf)?,
+                stay: Int::read_from(buf)?,
+                fade_out: Int::read_from(buf)?,
+            },
+            4 => TitleAction::Hide,
+            5 => TitleAction::Reset,
+            _ => {
+                return Err(Error::from(InvalidValue {
+                    expected: "0, 1, 2, 3, 4, 5".to_owned(),
+                }))
+            }
+        };
+
+        Ok(Event::Title(Self { action }))
+    }
+}
+
+impl V754Writable for Title {
+    fn v754_write<T: std::io::Write>(&self, buf: &mut T) -> TetsuResult<()> {
+        match &self.action {
+            TitleAction::SetTitle(text) => {
+                VarInt(0).write_to(buf)?;
+                text.write_to(buf)
+            }
+            TitleAction::SetSubtitle(text) => {
+                VarInt(1).write_to(buf)?;
+                text.write_to(buf)
+            }
+            TitleAction::SetActionBar(text) => {
+                VarInt(2).write_to(buf)?;
+                text.write_to(buf)
+            }
+            TitleAction::SetTimes {
+                fade_in,
+                stay,
+                fade_out,
+            } => {
+                VarInt(3).write_to(buf)?;
+                fade_in.write_to(buf)?;
+                stay.write_to(buf)?;
+                fade_out.write_to(buf)
+            }
+            TitleAction::Hide => VarInt(4).write_to(buf),
+            TitleAction::Reset => VarInt(5).write_to(buf),
+        }
+    }
+}